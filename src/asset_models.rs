@@ -1,15 +1,19 @@
 //! Submodule initializing trackable categories.
 
+use crate::definitions::TemplateDefinition;
+
 pub mod bead;
 pub use bead::*;
-// pub mod compatibility_rules;
+pub mod compatibility_rules;
+pub use compatibility_rules::*;
 pub mod containers;
 pub use containers::*;
 pub mod instruments;
 pub use instruments::*;
 pub mod markers;
 pub use markers::*;
-// pub mod organisms;
+pub mod organisms;
+pub use organisms::*;
 pub mod photographs;
 pub use photographs::*;
 pub mod panels;
@@ -18,8 +22,50 @@ pub mod ppe;
 pub use ppe::*;
 pub mod reagent_models;
 pub use reagent_models::*;
-// pub mod soils;
+pub mod soils;
+pub use soils::*;
 pub mod tools;
 pub use tools::*;
 
-// pub(crate) use compatibility_rules::init_compatibility_rules;
+/// Definitions of every reference template, parents before their children.
+pub const REFERENCE_TEMPLATES: &[TemplateDefinition] = &[
+    BEAD_3MM,
+    BOTTLE_1L,
+    POLYSTYRENE_BOX,
+    VIAL_RACK_1_5ML,
+    CONICAL_CENTRIFUGAL_TUBE_50ML,
+    STANDARD_RACK,
+    CONICAL_CENTRIFUGAL_TUBE_50ML_RACK,
+    SAFELOCK_TUBES_2ML,
+    SEALED_CAP_VIAL_1_5ML,
+    SPLIT_CAP_VIAL_1_5ML,
+    VIAL_INSERT_200UL,
+    VIAL_1_5ML,
+    COFFEE_FILTER_WRAPPER,
+    BALL_MILL_MACHINE,
+    SAFELOCK_CENTRIFUGE,
+    FREEZE_DRYER,
+    FREEZER,
+    PHONE_MODEL,
+    PIPETTE_TIP_200UL,
+    PIPETTE_TIP_1000UL,
+    PIPETTE_200UL,
+    PIPETTE_1000UL,
+    VOLUME_MEASURING_DEVICE_MODEL,
+    WEIGHING_SCALE,
+    MARKER_ARROW_MODEL,
+    PHOTOGRAPH_MODEL,
+    PANEL_MODEL,
+    GLOVE_MODEL,
+    DISTILLED_WATER,
+    ABSOLUTE_ETHANOL,
+    FORMIC_ACID,
+    LIQUID_NITROGEN,
+    METHANOL_HPLC,
+    SCALPEL_MODEL,
+    SCISSOR_MODEL,
+    ORGANISM_MODEL,
+    ORGANISM_SAMPLE_MODEL,
+    SOIL_MODEL,
+    SOIL_SAMPLE_MODEL,
+];
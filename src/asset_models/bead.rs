@@ -1,13 +1,33 @@
 //! Submodule to initialize bead models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_bead_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the 3mm metal bead model.
+pub const METAL_BEAD_3MM_NAME: &str = "Metal Bead 3mm";
+
+/// Diameter of the 3mm metal bead model, in millimeters.
+const BEAD_3MM_DIAMETER: f32 = 3.0;
+
+/// Definition of the 3mm metal bead model.
+pub const BEAD_3MM: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::BeadModels,
+    METAL_BEAD_3MM_NAME,
+    "Metal bead of 3mm used primarily in ball milling procedures.",
+)
+.diameter(BEAD_3MM_DIAMETER);
 
 /// Returns the 3mm metal bead model, creating it if it does not exist.
 ///
@@ -23,11 +43,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let bead_3mm_2 = bead_3mm(&test_user, &mut conn).expect("Failed to create the 3mm bead model");
 /// assert_eq!(bead_3mm_1, bead_3mm_2);
 /// ```
-pub fn bead_3mm<C>(
-    user: &User,
+pub fn bead_3mm<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<bead_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<bead_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<bead_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -36,32 +57,116 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<bead_models::table, C>,
 {
-    const METAL_BEAD_3MM_NAME: &str = "Metal Bead 3mm";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, METAL_BEAD_3MM_NAME, conn)
+        },
+        |conn| {
+            bead_models::table::builder()
+                .try_name(METAL_BEAD_3MM_NAME)
+                .map_err(TemplateError::attribute(METAL_BEAD_3MM_NAME, "name"))?
+                .try_description(BEAD_3MM.description)
+                .map_err(TemplateError::attribute(METAL_BEAD_3MM_NAME, "description"))?
+                .try_diameter(BEAD_3MM_DIAMETER)
+                .map_err(TemplateError::attribute(METAL_BEAD_3MM_NAME, "diameter"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, METAL_BEAD_3MM_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(METAL_BEAD_3MM_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the 3mm metal bead model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`Bead3mm`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_bead_3mm(&mut conn).expect("Failed to look up the 3mm metal bead model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = bead_3mm(&test_user, &mut conn).expect("Failed to create the 3mm metal bead model");
+/// assert_eq!(find_bead_3mm(&mut conn).expect("Failed to look up the 3mm metal bead model"), Some(model));
+/// ```
+pub fn find_bead_3mm<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<bead_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<bead_models::table, C>,
+{
+    Bead3mm::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the 3mm metal bead model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`Bead3mm`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_bead_3mm_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<bead_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<bead_models::table, C>,
+{
+    Bead3mm::find_in(namespace_name, conn)
+}
+
+/// Reference template of the 3mm metal bead model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bead3mm;
+
+impl AssetModelTemplate for Bead3mm {
+    type Table = bead_models::table;
+
+    const NAME: &'static str = METAL_BEAD_3MM_NAME;
+    const DESCRIPTION: &'static str = BEAD_3MM.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (METAL_BEAD_3MM_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        bead_3mm(context, conn)
     }
-
-    bead_models::table::builder()
-        .try_name(METAL_BEAD_3MM_NAME)
-        .expect("Failed to set bead model name")
-        .try_description("Metal bead of 3mm used primarily in ball milling procedures.")
-        .expect("Failed to set bead model description")
-        .try_diameter(3.0_f32)
-        .expect("Failed to set bead model diameter")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,15 +1,46 @@
 //! Submodule for defining the compatibility rules between different
 //! `asset_models`.
 
-use core_structures::User;
+use std::fmt::{self, Display};
 
 use crate::asset_models::compatibility_rules::{
-    ball_mill::init_ball_mill_rules,
-    conical_centrifugal_tube_rules::init_conical_centrifugal_tube_rules,
-    freeze_dryer::init_freeze_dryer_rules, freezer::init_freezer_rules,
-    pipette_rules::init_pipette_rules, safelock_tubes_rules::init_safelock_tubes_rules,
-    samples::init_sample_rules, vial_rules::init_vial_rules,
+    ball_mill::BALL_MILL_RULES, conical_centrifugal_tube_rules::CONICAL_CENTRIFUGAL_TUBE_RULES,
+    freeze_dryer::FREEZE_DRYER_RULES, freezer::FREEZER_RULES, pipette_rules::PIPETTE_RULES,
+    safelock_tubes_rules::SAFELOCK_TUBES_RULES, samples::SAMPLE_RULES, vial_rules::VIAL_RULES,
 };
+use crate::catalog::seed_reference_catalog;
+use crate::context::TemplateContext;
+use crate::definitions::TemplateDefinition;
+use crate::errors::TemplateError;
+use crate::lookup::{ReferenceReader, get_or_insert, optional};
+use crate::migrations::MigrationWriter;
+use aps::aps_asset_compatibility_rules::*;
+use aps::aps_ball_mill_machine_models::*;
+use aps::aps_bead_models::*;
+use aps::aps_centrifuge_models::*;
+use aps::aps_container_compatibility_rules::*;
+use aps::aps_container_models::*;
+use aps::aps_container_sealer_models::*;
+use aps::aps_digital_asset_models::*;
+use aps::aps_freeze_dryer_models::*;
+use aps::aps_freezer_models::*;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_packaging_models::*;
+use aps::aps_personal_protective_equipment_models::*;
+use aps::aps_phone_device_models::*;
+use aps::aps_physical_asset_models::*;
+use aps::aps_pipette_tip_models::*;
+use aps::aps_reagent_models::*;
+use aps::aps_sample_models::*;
+use aps::aps_sample_source_models::*;
+use aps::aps_users::*;
+use aps::aps_volume_measuring_device_models::*;
+use aps::aps_volumetric_container_models::*;
+use aps::aps_weighing_device_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+use rosetta_uuid::Uuid;
 
 mod ball_mill;
 mod conical_centrifugal_tube_rules;
@@ -20,27 +51,339 @@ mod safelock_tubes_rules;
 mod samples;
 mod vial_rules;
 
-/// Initializes the compatibility rules for `asset_models`.
+/// Compatibility rule between two reference models.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompatibilityRule {
+    /// The two models can be used together, e.g. a pipette and its tips.
+    Compatible {
+        /// Definition of the first model.
+        left: &'static TemplateDefinition,
+        /// Definition of the second model.
+        right: &'static TemplateDefinition,
+    },
+    /// The container model can hold up to `quantity` of the contained model.
+    Contains {
+        /// Definition of the container model.
+        container: &'static TemplateDefinition,
+        /// Definition of the contained model.
+        contained: &'static TemplateDefinition,
+        /// Maximum number of contained models fitting in the container.
+        quantity: i16,
+    },
+}
+
+impl CompatibilityRule {
+    /// Returns a rule stating that the two models can be used together.
+    #[must_use]
+    pub const fn compatible(
+        left: &'static TemplateDefinition,
+        right: &'static TemplateDefinition,
+    ) -> Self {
+        Self::Compatible { left, right }
+    }
+
+    /// Returns a rule stating that the container model can hold up to
+    /// `quantity` of the contained model.
+    #[must_use]
+    pub const fn contains(
+        container: &'static TemplateDefinition,
+        contained: &'static TemplateDefinition,
+        quantity: i16,
+    ) -> Self {
+        Self::Contains {
+            container,
+            contained,
+            quantity,
+        }
+    }
+}
+
+impl Display for CompatibilityRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compatible { left, right } => {
+                write!(f, "`{}` is compatible with `{}`", left.name, right.name)
+            }
+            Self::Contains {
+                container,
+                contained,
+                quantity,
+            } => write!(
+                f,
+                "`{}` can contain {quantity} `{}`",
+                container.name, contained.name
+            ),
+        }
+    }
+}
+
+/// Returns every compatibility rule between the reference models.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// assert!(compatibility_rules().any(|rule| *rule == CompatibilityRule::contains(&VIAL_RACK_1_5ML, &VIAL_1_5ML, 81)));
+/// assert!(compatibility_rules().any(|rule| *rule == CompatibilityRule::compatible(&PIPETTE_1000UL, &PIPETTE_TIP_1000UL)));
+/// assert!(compatibility_rules().any(|rule| *rule == CompatibilityRule::compatible(&BALL_MILL_MACHINE, &BEAD_3MM)));
+/// assert!(compatibility_rules().any(|rule| *rule == CompatibilityRule::contains(&CONICAL_CENTRIFUGAL_TUBE_50ML, &ORGANISM_SAMPLE_MODEL, 1)));
+/// ```
+pub fn compatibility_rules() -> impl Iterator<Item = &'static CompatibilityRule> {
+    [
+        CONICAL_CENTRIFUGAL_TUBE_RULES,
+        VIAL_RULES,
+        PIPETTE_RULES,
+        SAFELOCK_TUBES_RULES,
+        FREEZER_RULES,
+        FREEZE_DRYER_RULES,
+        BALL_MILL_RULES,
+        SAMPLE_RULES,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// Returns the identifier of the stored model of the provided definition,
+/// referenced by the provided rule.
+///
+/// # Errors
+///
+/// * If the lookup fails.
+/// * If the model does not exist.
+fn model_id<C: ReferenceReader>(
+    namespace: &NestedModel<namespaces::table>,
+    rule: &str,
+    definition: &TemplateDefinition,
+    conn: &mut C,
+) -> Result<Uuid, TemplateError> {
+    conn.find_stored_model(namespace, definition.table, definition.name)?
+        .map(|stored| stored.id)
+        .ok_or_else(|| TemplateError::MissingModel {
+            rule: rule.to_owned(),
+            template: definition.name.to_owned(),
+        })
+}
+
+/// Initializes the compatibility rules between the reference models, creating
+/// within a single transaction the models and the rules that do not exist yet,
+/// and returns the number of rules it created.
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the compatibility rules are being initialized.
-/// * `conn` - The database connection.
+/// * `context` - The namespace and users to create the missing models and
+///   rules with, or simply the creating user.
+/// * `conn` - The database connection to use for the insertions.
 ///
 /// # Errors
 ///
 /// * If the connection to the database fails.
-pub(crate) fn init_compatibility_rules(
-    user: &User,
-    conn: &mut diesel::PgConnection,
-) -> anyhow::Result<()> {
-    init_conical_centrifugal_tube_rules(user, conn)?;
-    init_vial_rules(user, conn)?;
-    init_pipette_rules(user, conn)?;
-    init_safelock_tubes_rules(user, conn)?;
-    init_freezer_rules(user, conn)?;
-    init_freeze_dryer_rules(user, conn)?;
-    init_ball_mill_rules(user, conn)?;
-    init_sample_rules(user, conn)?;
-    Ok(())
+/// * If any of the insertions fails, in which case none of them is committed.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let created = init_compatibility_rules(&test_user, &mut conn).expect("Failed to initialize the compatibility rules");
+/// assert_eq!(created, compatibility_rules().count());
+/// let created = init_compatibility_rules(&test_user, &mut conn).expect("Failed to initialize the compatibility rules");
+/// assert_eq!(created, 0);
+/// ```
+pub fn init_compatibility_rules<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<usize, TemplateError>
+where
+    C: MigrationWriter,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    TableBuilder<ball_mill_machine_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<ball_mill_machine_models::table, C>,
+    TableBuilder<bead_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<bead_models::table, C>,
+    TableBuilder<centrifuge_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<centrifuge_models::table, C>,
+    TableBuilder<container_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_models::table, C>,
+    TableBuilder<container_sealer_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+    TableBuilder<digital_asset_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<digital_asset_models::table, C>,
+    TableBuilder<freeze_dryer_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freeze_dryer_models::table, C>,
+    TableBuilder<freezer_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freezer_models::table, C>,
+    TableBuilder<packaging_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<packaging_models::table, C>,
+    TableBuilder<personal_protective_equipment_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<personal_protective_equipment_models::table, C>,
+    TableBuilder<phone_device_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<phone_device_models::table, C>,
+    TableBuilder<physical_asset_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+    TableBuilder<pipette_tip_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+    TableBuilder<reagent_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+    TableBuilder<sample_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+    TableBuilder<sample_source_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+    TableBuilder<volume_measuring_device_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+    TableBuilder<volumetric_container_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+    TableBuilder<weighing_device_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<weighing_device_models::table, C>,
+    TableBuilder<asset_compatibility_rules::table>: Insert<C>,
+    (
+        asset_compatibility_rules::left_asset_model_id,
+        (asset_compatibility_rules::right_asset_model_id,),
+    ): LoadNestedFirst<asset_compatibility_rules::table, C>,
+    TableBuilder<container_compatibility_rules::table>: Insert<C>,
+    (
+        container_compatibility_rules::container_model_id,
+        (container_compatibility_rules::contained_asset_model_id,),
+    ): LoadNestedFirst<container_compatibility_rules::table, C>,
+{
+    let context = context.into();
+    conn.transaction(|conn| {
+        let catalog = seed_reference_catalog(context, conn)?;
+        let namespace = &catalog.reference_namespace;
+        let mut created = 0;
+        for rule in compatibility_rules() {
+            let label = rule.to_string();
+            match *rule {
+                CompatibilityRule::Compatible { left, right } => {
+                    let left_id = model_id(namespace, &label, left, conn)?;
+                    let right_id = model_id(namespace, &label, right, conn)?;
+                    get_or_insert(
+                        |conn| {
+                            optional(
+                                &label,
+                                <(
+                                    asset_compatibility_rules::left_asset_model_id,
+                                    (asset_compatibility_rules::right_asset_model_id,),
+                                )>::load_nested_first(
+                                    (left_id, (right_id,)), conn
+                                ),
+                            )
+                        },
+                        |conn| {
+                            asset_compatibility_rules::table::builder()
+                                .try_left_asset_model_id(left_id)
+                                .map_err(TemplateError::attribute(&label, "left_asset_model_id"))?
+                                .try_right_asset_model_id(right_id)
+                                .map_err(TemplateError::attribute(&label, "right_asset_model_id"))?
+                                .creator_id(context.creator.get_column::<users::id>())
+                                .insert_nested(conn)
+                                .map_err(TemplateError::insert(&label))
+                                .inspect(|_| created += 1)
+                        },
+                        conn,
+                    )?;
+                }
+                CompatibilityRule::Contains {
+                    container,
+                    contained,
+                    quantity,
+                } => {
+                    let container_id = model_id(namespace, &label, container, conn)?;
+                    let contained_id = model_id(namespace, &label, contained, conn)?;
+                    get_or_insert(
+                        |conn| {
+                            optional(
+                                &label,
+                                <(
+                                    container_compatibility_rules::container_model_id,
+                                    (container_compatibility_rules::contained_asset_model_id,),
+                                )>::load_nested_first(
+                                    (container_id, (contained_id,)), conn
+                                ),
+                            )
+                        },
+                        |conn| {
+                            container_compatibility_rules::table::builder()
+                                .try_container_model_id(container_id)
+                                .map_err(TemplateError::attribute(&label, "container_model_id"))?
+                                .try_contained_asset_model_id(contained_id)
+                                .map_err(TemplateError::attribute(
+                                    &label,
+                                    "contained_asset_model_id",
+                                ))?
+                                .try_quantity(quantity)
+                                .map_err(TemplateError::attribute(&label, "quantity"))?
+                                .creator_id(context.creator.get_column::<users::id>())
+                                .insert_nested(conn)
+                                .map_err(TemplateError::insert(&label))
+                                .inspect(|_| created += 1)
+                        },
+                        conn,
+                    )?;
+                }
+            }
+        }
+        Ok(created)
+    })
 }
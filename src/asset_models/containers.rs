@@ -10,12 +10,41 @@ pub mod vial_inserts;
 pub mod vials;
 pub mod wrappers;
 
-pub use bottles::bottle_1l;
-pub use boxes::{polystyrene_box, vial_rack_1_5ml};
-pub use conical_centrifugal_tubes::conical_centrifugal_tube_50ml;
-pub use racks::conical_centrifugal_tube_50ml_rack;
-pub use safelock_tubes::safelock_tubes_2ml;
-pub use vial_caps::{sealed_cap_vial_1_5ml, splitted_cap_vial_1_5ml};
-pub use vial_inserts::vial_insert_200ul;
-pub use vials::vial_1_5ml;
-pub use wrappers::coffee_filter_wrapper;
+pub use bottles::{BOTTLE_1L, Bottle1l, bottle_1l, find_bottle_1l, find_bottle_1l_in};
+pub use boxes::{
+    POLYSTYRENE_BOX, PolystyreneBox, VIAL_RACK_1_5ML, VialRack1_5ml, find_polystyrene_box,
+    find_polystyrene_box_in, find_vial_rack_1_5ml, find_vial_rack_1_5ml_in, polystyrene_box,
+    vial_rack_1_5ml,
+};
+pub use conical_centrifugal_tubes::{
+    CONICAL_CENTRIFUGAL_TUBE_50ML, ConicalCentrifugalTube50ml, conical_centrifugal_tube_50ml,
+    find_conical_centrifugal_tube_50ml, find_conical_centrifugal_tube_50ml_in,
+};
+pub use racks::{
+    CONICAL_CENTRIFUGAL_TUBE_50ML_RACK, ConicalCentrifugalTube50mlRack, STANDARD_RACK,
+    conical_centrifugal_tube_50ml_rack, find_conical_centrifugal_tube_50ml_rack,
+    find_conical_centrifugal_tube_50ml_rack_in,
+};
+pub use safelock_tubes::{
+    SAFELOCK_TUBES_2ML, SafelockTubes2ml, find_safelock_tubes_2ml, find_safelock_tubes_2ml_in,
+    safelock_tubes_2ml,
+};
+pub use vial_caps::{
+    SEALED_CAP_VIAL_1_5ML, SPLIT_CAP_VIAL_1_5ML, SealedCapVial1_5ml, SplitCapVial1_5ml,
+    find_sealed_cap_vial_1_5ml, find_sealed_cap_vial_1_5ml_in, find_split_cap_vial_1_5ml,
+    find_split_cap_vial_1_5ml_in, sealed_cap_vial_1_5ml, split_cap_vial_1_5ml,
+};
+#[allow(deprecated)]
+pub use vial_caps::{
+    SPLITTED_CAP_VIAL_1_5ML, SplittedCapVial1_5ml, find_splitted_cap_vial_1_5ml,
+    splitted_cap_vial_1_5ml,
+};
+pub use vial_inserts::{
+    VIAL_INSERT_200UL, VialInsert200ul, find_vial_insert_200ul, find_vial_insert_200ul_in,
+    vial_insert_200ul,
+};
+pub use vials::{VIAL_1_5ML, Vial1_5ml, find_vial_1_5ml, find_vial_1_5ml_in, vial_1_5ml};
+pub use wrappers::{
+    COFFEE_FILTER_WRAPPER, CoffeeFilterWrapper, coffee_filter_wrapper, find_coffee_filter_wrapper,
+    find_coffee_filter_wrapper_in,
+};
@@ -1,13 +1,33 @@
 //! Submodule to initialize bottle models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volumetric_container_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the 1L bottle container model.
+pub const BOTTLE_1L_NAME: &str = "Bottle (1L)";
+
+/// Volume of the 1L bottle container model, in liters.
+const BOTTLE_1L_VOLUME: f32 = 1.0;
+
+/// Definition of the 1L bottle container model.
+pub const BOTTLE_1L: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumetricContainerModels,
+    BOTTLE_1L_NAME,
+    "Standard 1L bottle, used to store solvents and reagents.",
+)
+.volume(BOTTLE_1L_VOLUME);
 
 /// Returns the 1L bottle container model, creating it if it does not exist.
 ///
@@ -23,14 +43,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let bottle_1l_2 = bottle_1l(&test_user, &mut conn).expect("Failed to create bottle model");
 /// assert_eq!(bottle_1l_1, bottle_1l_2);
 /// ```
-pub fn bottle_1l<C>(
-    user: &User,
+pub fn bottle_1l<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volumetric_container_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volumetric_container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volumetric_container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -39,32 +57,115 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volumetric_container_models::table, C>,
 {
-    const BOTTLE_1L_NAME: &str = "Bottle (1L)";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, BOTTLE_1L_NAME, conn),
+        |conn| {
+            volumetric_container_models::table::builder()
+                .try_name(BOTTLE_1L_NAME)
+                .map_err(TemplateError::attribute(BOTTLE_1L_NAME, "name"))?
+                .try_description(BOTTLE_1L.description)
+                .map_err(TemplateError::attribute(BOTTLE_1L_NAME, "description"))?
+                .try_volume(BOTTLE_1L_VOLUME)
+                .map_err(TemplateError::attribute(BOTTLE_1L_NAME, "volume"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, BOTTLE_1L_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(BOTTLE_1L_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the 1L bottle container model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`Bottle1l`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_bottle_1l(&mut conn).expect("Failed to look up the 1L bottle container model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = bottle_1l(&test_user, &mut conn).expect("Failed to create the 1L bottle container model");
+/// assert_eq!(find_bottle_1l(&mut conn).expect("Failed to look up the 1L bottle container model"), Some(model));
+/// ```
+pub fn find_bottle_1l<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    Bottle1l::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the 1L bottle container model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`Bottle1l`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_bottle_1l_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    Bottle1l::find_in(namespace_name, conn)
+}
+
+/// Reference template of the 1L bottle container model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Bottle1l;
+
+impl AssetModelTemplate for Bottle1l {
+    type Table = volumetric_container_models::table;
+
+    const NAME: &'static str = BOTTLE_1L_NAME;
+    const DESCRIPTION: &'static str = BOTTLE_1L.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (BOTTLE_1L_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        bottle_1l(context, conn)
     }
-
-    volumetric_container_models::table::builder()
-        .try_name(BOTTLE_1L_NAME)
-        .expect("Failed to set volumetric container model name")
-        .try_description("Standard 1L bottle, used to store solvents and reagents.")
-        .expect("Failed to set volumetric container model description")
-        .try_volume(1.0_f32)
-        .expect("Failed to set volumetric container model volume")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
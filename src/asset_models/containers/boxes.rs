@@ -1,13 +1,29 @@
 //! Submodule defining standard box and rack-like container models.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_container_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the polystyrene box model.
+pub const POLYSTYRENE_BOX_NAME: &str = "Polystyrene Box";
+
+/// Definition of the polystyrene box model.
+pub const POLYSTYRENE_BOX: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ContainerModels,
+    POLYSTYRENE_BOX_NAME,
+    "Polystyrene box, a container typically used for liquid nitrogen",
+);
 
 /// Returns the polystyrene box model, creating it if it does not exist.
 ///
@@ -23,11 +39,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let polystyrene_box2 = polystyrene_box(&test_user, &mut conn).expect("Failed to create polystyrene box model");
 /// assert_eq!(polystyrene_box1, polystyrene_box2);
 /// ```
-pub fn polystyrene_box<C>(
-    user: &User,
+pub fn polystyrene_box<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<container_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -36,34 +53,131 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<container_models::table, C>,
 {
-    const POLYSTYRENE_BOX_NAME: &str = "Polystyrene Box";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, POLYSTYRENE_BOX_NAME, conn)
+        },
+        |conn| {
+            container_models::table::builder()
+                .try_name(POLYSTYRENE_BOX_NAME)
+                .map_err(TemplateError::attribute(POLYSTYRENE_BOX_NAME, "name"))?
+                .try_description(POLYSTYRENE_BOX.description)
+                .map_err(TemplateError::attribute(
+                    POLYSTYRENE_BOX_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, POLYSTYRENE_BOX_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(POLYSTYRENE_BOX_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the polystyrene box model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`PolystyreneBox`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_polystyrene_box(&mut conn).expect("Failed to look up the polystyrene box model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = polystyrene_box(&test_user, &mut conn).expect("Failed to create the polystyrene box model");
+/// assert_eq!(find_polystyrene_box(&mut conn).expect("Failed to look up the polystyrene box model"), Some(model));
+/// ```
+pub fn find_polystyrene_box<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<container_models::table, C>,
+{
+    PolystyreneBox::find(conn)
+}
+
+/// Returns the polystyrene box model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`PolystyreneBox`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_polystyrene_box_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_models::table, C>,
+{
+    PolystyreneBox::find_in(namespace_name, conn)
+}
+
+/// Reference template of the polystyrene box model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PolystyreneBox;
+
+impl AssetModelTemplate for PolystyreneBox {
+    type Table = container_models::table;
+
+    const NAME: &'static str = POLYSTYRENE_BOX_NAME;
+    const DESCRIPTION: &'static str = POLYSTYRENE_BOX.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (POLYSTYRENE_BOX_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        polystyrene_box(context, conn)
     }
-
-    container_models::table::builder()
-        .try_name(POLYSTYRENE_BOX_NAME)
-        .expect("Failed to set container model name")
-        .try_description("Polystyrene box, a container typically used for liquid nitrogen")
-        .expect("Failed to set container model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
 
+/// Name of the vial rack 1.5ml model.
+pub const VIAL_RACK_1_5ML_NAME: &str = "Vial Rack 1.5ml (9x9)";
+
+/// Definition of the vial rack 1.5ml model.
+pub const VIAL_RACK_1_5ML: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ContainerModels,
+    VIAL_RACK_1_5ML_NAME,
+    "Vial box, a container typically used for storing vials",
+);
+
 /// Returns the vial rack 1.5ml model, creating it if it does not exist.
 ///
 /// # Example
@@ -78,11 +192,12 @@ where
 /// let vial_rack2 = vial_rack_1_5ml(&test_user, &mut conn).expect("Failed to create vial rack model");
 /// assert_eq!(vial_rack1, vial_rack2);
 /// ```
-pub fn vial_rack_1_5ml<C>(
-    user: &User,
+pub fn vial_rack_1_5ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<container_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -91,30 +206,117 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<container_models::table, C>,
 {
-    const VIAL_RACK_1_5ML_NAME: &str = "Vial Rack 1.5ml (9x9)";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, VIAL_RACK_1_5ML_NAME, conn)
+        },
+        |conn| {
+            container_models::table::builder()
+                .try_name(VIAL_RACK_1_5ML_NAME)
+                .map_err(TemplateError::attribute(VIAL_RACK_1_5ML_NAME, "name"))?
+                .try_description(VIAL_RACK_1_5ML.description)
+                .map_err(TemplateError::attribute(
+                    VIAL_RACK_1_5ML_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, VIAL_RACK_1_5ML_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(VIAL_RACK_1_5ML_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the vial rack 1.5ml model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`VialRack1_5ml`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_vial_rack_1_5ml(&mut conn).expect("Failed to look up the vial rack 1.5ml model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = vial_rack_1_5ml(&test_user, &mut conn).expect("Failed to create the vial rack 1.5ml model");
+/// assert_eq!(find_vial_rack_1_5ml(&mut conn).expect("Failed to look up the vial rack 1.5ml model"), Some(model));
+/// ```
+pub fn find_vial_rack_1_5ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<container_models::table, C>,
+{
+    VialRack1_5ml::find(conn)
+}
+
+/// Returns the vial rack 1.5ml model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`VialRack1_5ml`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_vial_rack_1_5ml_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_models::table, C>,
+{
+    VialRack1_5ml::find_in(namespace_name, conn)
+}
+
+/// Reference template of the vial rack 1.5ml model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VialRack1_5ml;
+
+impl AssetModelTemplate for VialRack1_5ml {
+    type Table = container_models::table;
+
+    const NAME: &'static str = VIAL_RACK_1_5ML_NAME;
+    const DESCRIPTION: &'static str = VIAL_RACK_1_5ML.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (VIAL_RACK_1_5ML_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        vial_rack_1_5ml(context, conn)
     }
-
-    container_models::table::builder()
-        .try_name(VIAL_RACK_1_5ML_NAME)
-        .expect("Failed to set container model name")
-        .try_description("Vial box, a container typically used for storing vials")
-        .expect("Failed to set container model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,13 +1,33 @@
 //! Submodule to initialize conical centrifugal tube models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volumetric_container_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the conical centrifugal tube 50ml model.
+pub const CCT_50ML_NAME: &str = "Conical Centrifugal Tube 50ml";
+
+/// Volume of the conical centrifugal tube 50ml model, in liters.
+const CONICAL_CENTRIFUGAL_TUBE_50ML_VOLUME: f32 = 0.05;
+
+/// Definition of the conical centrifugal tube 50ml model.
+pub const CONICAL_CENTRIFUGAL_TUBE_50ML: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumetricContainerModels,
+    CCT_50ML_NAME,
+    "Conical tube of 50ml, used for sample collection.",
+)
+.volume(CONICAL_CENTRIFUGAL_TUBE_50ML_VOLUME);
 
 /// Returns the conical centrifugal tube 50ml model, creating it if it does not
 /// exist.
@@ -24,14 +44,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let cct2 = conical_centrifugal_tube_50ml(&test_user, &mut conn).expect("Failed to create conical centrifugal tube model");
 /// assert_eq!(cct1, cct2);
 /// ```
-pub fn conical_centrifugal_tube_50ml<C>(
-    user: &User,
+pub fn conical_centrifugal_tube_50ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volumetric_container_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volumetric_container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volumetric_container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -40,32 +58,117 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volumetric_container_models::table, C>,
 {
-    const CCT_50ML_NAME: &str = "Conical Centrifugal Tube 50ml";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, CCT_50ML_NAME, conn),
+        |conn| {
+            volumetric_container_models::table::builder()
+                .try_name(CCT_50ML_NAME)
+                .map_err(TemplateError::attribute(CCT_50ML_NAME, "name"))?
+                .try_description(CONICAL_CENTRIFUGAL_TUBE_50ML.description)
+                .map_err(TemplateError::attribute(CCT_50ML_NAME, "description"))?
+                .try_volume(CONICAL_CENTRIFUGAL_TUBE_50ML_VOLUME)
+                .map_err(TemplateError::attribute(CCT_50ML_NAME, "volume"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, CCT_50ML_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(CCT_50ML_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the conical centrifugal tube 50ml model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on
+/// [`ConicalCentrifugalTube50ml`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_conical_centrifugal_tube_50ml(&mut conn).expect("Failed to look up the conical centrifugal tube 50ml model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = conical_centrifugal_tube_50ml(&test_user, &mut conn).expect("Failed to create the conical centrifugal tube 50ml model");
+/// assert_eq!(find_conical_centrifugal_tube_50ml(&mut conn).expect("Failed to look up the conical centrifugal tube 50ml model"), Some(model));
+/// ```
+pub fn find_conical_centrifugal_tube_50ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    ConicalCentrifugalTube50ml::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the conical centrifugal tube 50ml model from the provided namespace,
+/// if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on
+/// [`ConicalCentrifugalTube50ml`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_conical_centrifugal_tube_50ml_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    ConicalCentrifugalTube50ml::find_in(namespace_name, conn)
+}
+
+/// Reference template of the conical centrifugal tube 50ml model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ConicalCentrifugalTube50ml;
+
+impl AssetModelTemplate for ConicalCentrifugalTube50ml {
+    type Table = volumetric_container_models::table;
+
+    const NAME: &'static str = CCT_50ML_NAME;
+    const DESCRIPTION: &'static str = CONICAL_CENTRIFUGAL_TUBE_50ML.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (CCT_50ML_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        conical_centrifugal_tube_50ml(context, conn)
     }
-
-    volumetric_container_models::table::builder()
-        .try_name(CCT_50ML_NAME)
-        .expect("Failed to set volumetric container model name")
-        .try_description("Conical tube of 50ml, used for sample collection.")
-        .expect("Failed to set volumetric container model description")
-        .try_volume(0.05_f32)
-        .expect("Failed to set volumetric container model volume")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,20 +1,37 @@
 //! Submodule to initialize rack container models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_asset_models::TrySetAssetModelParentModelId;
 use aps::aps_container_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
 
-fn standard_rack<C>(
-    user: &User,
+/// Name of the standard rack model.
+pub const STANDARD_RACK_NAME: &str = "Rack";
+
+/// Definition of the standard rack model.
+pub const STANDARD_RACK: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ContainerModels,
+    STANDARD_RACK_NAME,
+    "Rack, a common container for organizing samples",
+);
+
+fn standard_rack<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<container_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -23,34 +40,41 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<container_models::table, C>,
 {
-    const STANDARD_RACK_NAME: &str = "Rack";
-
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
-        namespaced_ownables::namespace_id,
-        (namespaced_ownables::name,),
-    )>::load_nested_first(
-        (
-            reference_namespace.get_column::<namespaces::id>(),
-            (STANDARD_RACK_NAME,),
-        ),
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, STANDARD_RACK_NAME, conn)
+        },
+        |conn| {
+            container_models::table::builder()
+                .try_name(STANDARD_RACK_NAME)
+                .map_err(TemplateError::attribute(STANDARD_RACK_NAME, "name"))?
+                .try_description(STANDARD_RACK.description)
+                .map_err(TemplateError::attribute(STANDARD_RACK_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, STANDARD_RACK_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(STANDARD_RACK_NAME))
+        },
         conn,
-    ) {
-        return Ok(existing);
-    }
-
-    container_models::table::builder()
-        .try_name(STANDARD_RACK_NAME)
-        .expect("Failed to set container model name")
-        .try_description("Rack, a common container for organizing samples")
-        .expect("Failed to set container model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
+    )
 }
 
+/// Name of the rack model for conical centrifugal tubes of 50ml.
+pub const CCT_RACK_50ML_NAME: &str = "Conical Centrifugal Tube 50ml Rack";
+
+/// Definition of the rack model for conical centrifugal tubes of 50ml.
+pub const CONICAL_CENTRIFUGAL_TUBE_50ML_RACK: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ContainerModels,
+    CCT_RACK_50ML_NAME,
+    "Rack for storing conical centrifugal tubes of 50ml",
+)
+.parent(&STANDARD_RACK);
+
 /// Returns the rack model for conical centrifugal tubes of 50ml, creating it
 /// if it does not exist.
 ///
@@ -66,11 +90,12 @@ where
 /// let rack2 = conical_centrifugal_tube_50ml_rack(&test_user, &mut conn).expect("Failed to create conical centrifugal tube rack model");
 /// assert_eq!(rack1, rack2);
 /// ```
-pub fn conical_centrifugal_tube_50ml_rack<C>(
-    user: &User,
+pub fn conical_centrifugal_tube_50ml_rack<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<container_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -79,33 +104,123 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<container_models::table, C>,
 {
-    const CCT_RACK_50ML_NAME: &str = "Conical Centrifugal Tube 50ml Rack";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, CCT_RACK_50ML_NAME, conn)
+        },
+        |conn| {
+            let standard_rack = standard_rack(context, conn)?;
+            container_models::table::builder()
+                .try_name(CCT_RACK_50ML_NAME)
+                .map_err(TemplateError::attribute(CCT_RACK_50ML_NAME, "name"))?
+                .try_description(CONICAL_CENTRIFUGAL_TUBE_50ML_RACK.description)
+                .map_err(TemplateError::attribute(CCT_RACK_50ML_NAME, "description"))?
+                .try_parent_model_id(standard_rack.get_column::<container_models::id>())
+                .map_err(TemplateError::attribute(
+                    CCT_RACK_50ML_NAME,
+                    "parent_model_id",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, CCT_RACK_50ML_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(CCT_RACK_50ML_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the rack model for conical centrifugal tubes of 50ml, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on
+/// [`ConicalCentrifugalTube50mlRack`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_conical_centrifugal_tube_50ml_rack(&mut conn).expect("Failed to look up the rack model for conical centrifugal tubes of 50ml").is_none());
+/// let test_user = user(&mut conn);
+/// let model = conical_centrifugal_tube_50ml_rack(&test_user, &mut conn).expect("Failed to create the rack model for conical centrifugal tubes of 50ml");
+/// assert_eq!(find_conical_centrifugal_tube_50ml_rack(&mut conn).expect("Failed to look up the rack model for conical centrifugal tubes of 50ml"), Some(model));
+/// ```
+pub fn find_conical_centrifugal_tube_50ml_rack<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_models::table, C>,
+{
+    ConicalCentrifugalTube50mlRack::find(conn)
+}
+
+/// Returns the rack model for conical centrifugal tubes of 50ml from the
+/// provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on
+/// [`ConicalCentrifugalTube50mlRack`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_conical_centrifugal_tube_50ml_rack_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<container_models::table, C>,
+{
+    ConicalCentrifugalTube50mlRack::find_in(namespace_name, conn)
+}
+
+/// Reference template of the rack model for conical centrifugal tubes of 50ml.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ConicalCentrifugalTube50mlRack;
+
+impl AssetModelTemplate for ConicalCentrifugalTube50mlRack {
+    type Table = container_models::table;
+
+    const NAME: &'static str = CCT_RACK_50ML_NAME;
+    const DESCRIPTION: &'static str = CONICAL_CENTRIFUGAL_TUBE_50ML_RACK.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (CCT_RACK_50ML_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        conical_centrifugal_tube_50ml_rack(context, conn)
     }
-
-    let standard_rack = standard_rack(user, conn)?;
-    container_models::table::builder()
-        .try_name(CCT_RACK_50ML_NAME)
-        .expect("Failed to set container model name")
-        .try_description("Rack for storing conical centrifugal tubes of 50ml")
-        .expect("Failed to set container model description")
-        .try_parent_model_id(standard_rack.get_column::<container_models::id>())
-        .expect("Failed to set container model parent model")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
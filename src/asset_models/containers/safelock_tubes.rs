@@ -1,13 +1,33 @@
 //! Submodule to initialize safelock tube models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volumetric_container_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the safelock tube 2ml model.
+pub const SAFELOCK_2ML_NAME: &str = "Safelock Tube 2ml";
+
+/// Volume of the safelock tube 2ml model, in liters.
+const SAFELOCK_TUBES_2ML_VOLUME: f32 = 0.002;
+
+/// Definition of the safelock tube 2ml model.
+pub const SAFELOCK_TUBES_2ML: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumetricContainerModels,
+    SAFELOCK_2ML_NAME,
+    "Safelock tube of 2ml, used for sample extraction.",
+)
+.volume(SAFELOCK_TUBES_2ML_VOLUME);
 
 /// Returns the safelock tube 2ml model, creating it if it does not exist.
 ///
@@ -23,14 +43,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let safelock2 = safelock_tubes_2ml(&test_user, &mut conn).expect("Failed to create safelock tube model");
 /// assert_eq!(safelock1, safelock2);
 /// ```
-pub fn safelock_tubes_2ml<C>(
-    user: &User,
+pub fn safelock_tubes_2ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volumetric_container_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volumetric_container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volumetric_container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -39,32 +57,115 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volumetric_container_models::table, C>,
 {
-    const SAFELOCK_2ML_NAME: &str = "Safelock Tube 2ml";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SAFELOCK_2ML_NAME, conn),
+        |conn| {
+            volumetric_container_models::table::builder()
+                .try_name(SAFELOCK_2ML_NAME)
+                .map_err(TemplateError::attribute(SAFELOCK_2ML_NAME, "name"))?
+                .try_description(SAFELOCK_TUBES_2ML.description)
+                .map_err(TemplateError::attribute(SAFELOCK_2ML_NAME, "description"))?
+                .try_volume(SAFELOCK_TUBES_2ML_VOLUME)
+                .map_err(TemplateError::attribute(SAFELOCK_2ML_NAME, "volume"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SAFELOCK_2ML_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SAFELOCK_2ML_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the safelock tube 2ml model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`SafelockTubes2ml`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_safelock_tubes_2ml(&mut conn).expect("Failed to look up the safelock tube 2ml model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = safelock_tubes_2ml(&test_user, &mut conn).expect("Failed to create the safelock tube 2ml model");
+/// assert_eq!(find_safelock_tubes_2ml(&mut conn).expect("Failed to look up the safelock tube 2ml model"), Some(model));
+/// ```
+pub fn find_safelock_tubes_2ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    SafelockTubes2ml::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the safelock tube 2ml model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`SafelockTubes2ml`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_safelock_tubes_2ml_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    SafelockTubes2ml::find_in(namespace_name, conn)
+}
+
+/// Reference template of the safelock tube 2ml model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SafelockTubes2ml;
+
+impl AssetModelTemplate for SafelockTubes2ml {
+    type Table = volumetric_container_models::table;
+
+    const NAME: &'static str = SAFELOCK_2ML_NAME;
+    const DESCRIPTION: &'static str = SAFELOCK_TUBES_2ML.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (SAFELOCK_2ML_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        safelock_tubes_2ml(context, conn)
     }
-
-    volumetric_container_models::table::builder()
-        .try_name(SAFELOCK_2ML_NAME)
-        .expect("Failed to set volumetric container model name")
-        .try_description("Safelock tube of 2ml, used for sample extraction.")
-        .expect("Failed to set volumetric container model description")
-        .try_volume(0.002_f32)
-        .expect("Failed to set volumetric container model volume")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
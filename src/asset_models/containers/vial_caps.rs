@@ -1,15 +1,33 @@
 //! Submodule to initialize vial cap (container sealer) models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_container_sealer_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
 
-/// Returns the splitted cap for vial 1.5ml model, creating it if it does not
+/// Name of the split cap for vial 1.5ml model, formerly "Splitted Cap for
+/// Vial 1.5ml" and renamed by the `0002_split_cap_vial_1_5ml` migration of the
+/// [`TEMPLATE_MIGRATIONS`](crate::migrations::TEMPLATE_MIGRATIONS).
+pub const SPLIT_CAP_NAME: &str = "Split Cap for Vial 1.5ml";
+
+/// Definition of the split cap for vial 1.5ml model.
+pub const SPLIT_CAP_VIAL_1_5ML: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ContainerSealerModels,
+    SPLIT_CAP_NAME,
+    "Split cap for Vial of 1.5 ml used for extracts storage",
+);
+
+/// Returns the split cap for vial 1.5ml model, creating it if it does not
 /// exist.
 ///
 /// # Example
@@ -20,18 +38,16 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let mut conn = aps_git_conn();
 ///
 /// let test_user = user(&mut conn);
-/// let split1 = splitted_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create splitted cap model");
-/// let split2 = splitted_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create splitted cap model");
+/// let split1 = split_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create split cap model");
+/// let split2 = split_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create split cap model");
 /// assert_eq!(split1, split2);
 /// ```
-pub fn splitted_cap_vial_1_5ml<C>(
-    user: &User,
+pub fn split_cap_vial_1_5ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<container_sealer_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<container_sealer_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<container_sealer_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -40,34 +56,181 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<container_sealer_models::table, C>,
 {
-    const SPLITTED_CAP_NAME: &str = "Splitted Cap for Vial 1.5ml";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SPLIT_CAP_NAME, conn),
+        |conn| {
+            container_sealer_models::table::builder()
+                .try_name(SPLIT_CAP_NAME)
+                .map_err(TemplateError::attribute(SPLIT_CAP_NAME, "name"))?
+                .try_description(SPLIT_CAP_VIAL_1_5ML.description)
+                .map_err(TemplateError::attribute(SPLIT_CAP_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SPLIT_CAP_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SPLIT_CAP_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the split cap for vial 1.5ml model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`SplitCapVial1_5ml`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_split_cap_vial_1_5ml(&mut conn).expect("Failed to look up the split cap for vial 1.5ml model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = split_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create the split cap for vial 1.5ml model");
+/// assert_eq!(find_split_cap_vial_1_5ml(&mut conn).expect("Failed to look up the split cap for vial 1.5ml model"), Some(model));
+/// ```
+pub fn find_split_cap_vial_1_5ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_sealer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+{
+    SplitCapVial1_5ml::find(conn)
+}
+
+/// Returns the split cap for vial 1.5ml model from the provided namespace, if
+/// it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`SplitCapVial1_5ml`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_split_cap_vial_1_5ml_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_sealer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+{
+    SplitCapVial1_5ml::find_in(namespace_name, conn)
+}
+
+/// Reference template of the split cap for vial 1.5ml model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SplitCapVial1_5ml;
+
+impl AssetModelTemplate for SplitCapVial1_5ml {
+    type Table = container_sealer_models::table;
+
+    const NAME: &'static str = SPLIT_CAP_NAME;
+    const DESCRIPTION: &'static str = SPLIT_CAP_VIAL_1_5ML.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (SPLITTED_CAP_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        split_cap_vial_1_5ml(context, conn)
     }
+}
 
-    container_sealer_models::table::builder()
-        .try_name(SPLITTED_CAP_NAME)
-        .expect("Failed to set container sealer model name")
-        .try_description("Splitted cap for Vial of 1.5 ml used for extracts storage")
-        .expect("Failed to set container sealer model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
+/// Former name of [`SPLIT_CAP_NAME`].
+#[deprecated(note = "renamed to `SPLIT_CAP_NAME`")]
+pub const SPLITTED_CAP_NAME: &str = SPLIT_CAP_NAME;
+
+/// Former name of [`SPLIT_CAP_VIAL_1_5ML`].
+#[deprecated(note = "renamed to `SPLIT_CAP_VIAL_1_5ML`")]
+pub const SPLITTED_CAP_VIAL_1_5ML: TemplateDefinition = SPLIT_CAP_VIAL_1_5ML;
+
+/// Former name of [`SplitCapVial1_5ml`].
+#[deprecated(note = "renamed to `SplitCapVial1_5ml`")]
+pub type SplittedCapVial1_5ml = SplitCapVial1_5ml;
+
+/// Former name of [`split_cap_vial_1_5ml`].
+///
+/// # Errors
+///
+/// * See [`split_cap_vial_1_5ml`].
+#[deprecated(note = "renamed to `split_cap_vial_1_5ml`")]
+pub fn splitted_cap_vial_1_5ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<NestedModel<container_sealer_models::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<container_sealer_models::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+{
+    split_cap_vial_1_5ml(context, conn)
 }
 
+/// Former name of [`find_split_cap_vial_1_5ml`].
+///
+/// # Errors
+///
+/// * See [`find_split_cap_vial_1_5ml`].
+#[deprecated(note = "renamed to `find_split_cap_vial_1_5ml`")]
+pub fn find_splitted_cap_vial_1_5ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_sealer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+{
+    find_split_cap_vial_1_5ml(conn)
+}
+
+/// Name of the sealed cap for vial 1.5ml model.
+pub const SEALED_CAP_NAME: &str = "Sealed Cap for Vial 1.5ml";
+
+/// Definition of the sealed cap for vial 1.5ml model.
+pub const SEALED_CAP_VIAL_1_5ML: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ContainerSealerModels,
+    SEALED_CAP_NAME,
+    "Sealed cap for Vial of 1.5 ml used for extracts storage",
+);
+
 /// Returns the sealed cap for vial 1.5ml model, creating it if it does not
 /// exist.
 ///
@@ -83,14 +246,12 @@ where
 /// let sealed2 = sealed_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create sealed cap model");
 /// assert_eq!(sealed1, sealed2);
 /// ```
-pub fn sealed_cap_vial_1_5ml<C>(
-    user: &User,
+pub fn sealed_cap_vial_1_5ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<container_sealer_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<container_sealer_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<container_sealer_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -99,30 +260,113 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<container_sealer_models::table, C>,
 {
-    const SEALED_CAP_NAME: &str = "Sealed Cap for Vial 1.5ml";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SEALED_CAP_NAME, conn),
+        |conn| {
+            container_sealer_models::table::builder()
+                .try_name(SEALED_CAP_NAME)
+                .map_err(TemplateError::attribute(SEALED_CAP_NAME, "name"))?
+                .try_description(SEALED_CAP_VIAL_1_5ML.description)
+                .map_err(TemplateError::attribute(SEALED_CAP_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SEALED_CAP_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SEALED_CAP_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the sealed cap for vial 1.5ml model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`SealedCapVial1_5ml`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_sealed_cap_vial_1_5ml(&mut conn).expect("Failed to look up the sealed cap for vial 1.5ml model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = sealed_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create the sealed cap for vial 1.5ml model");
+/// assert_eq!(find_sealed_cap_vial_1_5ml(&mut conn).expect("Failed to look up the sealed cap for vial 1.5ml model"), Some(model));
+/// ```
+pub fn find_sealed_cap_vial_1_5ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_sealer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+{
+    SealedCapVial1_5ml::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the sealed cap for vial 1.5ml model from the provided namespace, if
+/// it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`SealedCapVial1_5ml`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_sealed_cap_vial_1_5ml_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<container_sealer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+{
+    SealedCapVial1_5ml::find_in(namespace_name, conn)
+}
+
+/// Reference template of the sealed cap for vial 1.5ml model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SealedCapVial1_5ml;
+
+impl AssetModelTemplate for SealedCapVial1_5ml {
+    type Table = container_sealer_models::table;
+
+    const NAME: &'static str = SEALED_CAP_NAME;
+    const DESCRIPTION: &'static str = SEALED_CAP_VIAL_1_5ML.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (SEALED_CAP_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        sealed_cap_vial_1_5ml(context, conn)
     }
-
-    container_sealer_models::table::builder()
-        .try_name(SEALED_CAP_NAME)
-        .expect("Failed to set container sealer model name")
-        .try_description("Sealed cap for Vial of 1.5 ml used for extracts storage")
-        .expect("Failed to set container sealer model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
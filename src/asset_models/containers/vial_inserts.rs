@@ -1,13 +1,33 @@
 //! Submodule to initialize vial insert models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volumetric_container_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the vial insert 200μl model.
+pub const VIAL_INSERT_200UL_NAME: &str = "Vial Insert 200μl";
+
+/// Volume of the vial insert 200μl model, in liters.
+const VIAL_INSERT_200UL_VOLUME: f32 = 0.0002;
+
+/// Definition of the vial insert 200μl model.
+pub const VIAL_INSERT_200UL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumetricContainerModels,
+    VIAL_INSERT_200UL_NAME,
+    "Vial insert of 200μl, used to hold samples in vials.",
+)
+.volume(VIAL_INSERT_200UL_VOLUME);
 
 /// Returns the vial insert 200μl model, creating it if it does not exist.
 ///
@@ -23,14 +43,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let insert2 = vial_insert_200ul(&test_user, &mut conn).expect("Failed to create vial insert model");
 /// assert_eq!(insert1, insert2);
 /// ```
-pub fn vial_insert_200ul<C>(
-    user: &User,
+pub fn vial_insert_200ul<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volumetric_container_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volumetric_container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volumetric_container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -39,32 +57,125 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volumetric_container_models::table, C>,
 {
-    const VIAL_INSERT_200UL_NAME: &str = "Vial Insert 200μl";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(
+                &context,
+                &reference_namespace,
+                VIAL_INSERT_200UL_NAME,
+                conn,
+            )
+        },
+        |conn| {
+            volumetric_container_models::table::builder()
+                .try_name(VIAL_INSERT_200UL_NAME)
+                .map_err(TemplateError::attribute(VIAL_INSERT_200UL_NAME, "name"))?
+                .try_description(VIAL_INSERT_200UL.description)
+                .map_err(TemplateError::attribute(
+                    VIAL_INSERT_200UL_NAME,
+                    "description",
+                ))?
+                .try_volume(VIAL_INSERT_200UL_VOLUME)
+                .map_err(TemplateError::attribute(VIAL_INSERT_200UL_NAME, "volume"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, VIAL_INSERT_200UL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(VIAL_INSERT_200UL_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the vial insert 200μl model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`VialInsert200ul`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_vial_insert_200ul(&mut conn).expect("Failed to look up the vial insert 200μl model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = vial_insert_200ul(&test_user, &mut conn).expect("Failed to create the vial insert 200μl model");
+/// assert_eq!(find_vial_insert_200ul(&mut conn).expect("Failed to look up the vial insert 200μl model"), Some(model));
+/// ```
+pub fn find_vial_insert_200ul<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    VialInsert200ul::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the vial insert 200μl model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`VialInsert200ul`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_vial_insert_200ul_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    VialInsert200ul::find_in(namespace_name, conn)
+}
+
+/// Reference template of the vial insert 200μl model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VialInsert200ul;
+
+impl AssetModelTemplate for VialInsert200ul {
+    type Table = volumetric_container_models::table;
+
+    const NAME: &'static str = VIAL_INSERT_200UL_NAME;
+    const DESCRIPTION: &'static str = VIAL_INSERT_200UL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (VIAL_INSERT_200UL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        vial_insert_200ul(context, conn)
     }
-
-    volumetric_container_models::table::builder()
-        .try_name(VIAL_INSERT_200UL_NAME)
-        .expect("Failed to set volumetric container model name")
-        .try_description("Vial insert of 200μl, used to hold samples in vials.")
-        .expect("Failed to set volumetric container model description")
-        .try_volume(0.0002_f32)
-        .expect("Failed to set volumetric container model volume")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,13 +1,33 @@
 //! Submodule to initialize vial models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volumetric_container_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the vial 1.5ml model.
+pub const VIAL_1_5ML_NAME: &str = "Vial 1.5ml";
+
+/// Volume of the vial 1.5ml model, in liters.
+const VIAL_1_5ML_VOLUME: f32 = 0.0015;
+
+/// Definition of the vial 1.5ml model.
+pub const VIAL_1_5ML: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumetricContainerModels,
+    VIAL_1_5ML_NAME,
+    "Vial of 1.5 ml used for extracts storage",
+)
+.volume(VIAL_1_5ML_VOLUME);
 
 /// Returns the vial 1.5ml model, creating it if it does not exist.
 ///
@@ -23,14 +43,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let vial2 = vial_1_5ml(&test_user, &mut conn).expect("Failed to create vial model");
 /// assert_eq!(vial1, vial2);
 /// ```
-pub fn vial_1_5ml<C>(
-    user: &User,
+pub fn vial_1_5ml<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volumetric_container_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volumetric_container_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volumetric_container_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -39,32 +57,114 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volumetric_container_models::table, C>,
 {
-    const VIAL_1_5ML_NAME: &str = "Vial 1.5ml";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, VIAL_1_5ML_NAME, conn),
+        |conn| {
+            volumetric_container_models::table::builder()
+                .try_name(VIAL_1_5ML_NAME)
+                .map_err(TemplateError::attribute(VIAL_1_5ML_NAME, "name"))?
+                .try_description(VIAL_1_5ML.description)
+                .map_err(TemplateError::attribute(VIAL_1_5ML_NAME, "description"))?
+                .try_volume(VIAL_1_5ML_VOLUME)
+                .map_err(TemplateError::attribute(VIAL_1_5ML_NAME, "volume"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, VIAL_1_5ML_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(VIAL_1_5ML_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the vial 1.5ml model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`Vial1_5ml`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_vial_1_5ml(&mut conn).expect("Failed to look up the vial 1.5ml model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = vial_1_5ml(&test_user, &mut conn).expect("Failed to create the vial 1.5ml model");
+/// assert_eq!(find_vial_1_5ml(&mut conn).expect("Failed to look up the vial 1.5ml model"), Some(model));
+/// ```
+pub fn find_vial_1_5ml<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    Vial1_5ml::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the vial 1.5ml model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`Vial1_5ml`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_vial_1_5ml_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volumetric_container_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+{
+    Vial1_5ml::find_in(namespace_name, conn)
+}
+
+/// Reference template of the vial 1.5ml model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Vial1_5ml;
+
+impl AssetModelTemplate for Vial1_5ml {
+    type Table = volumetric_container_models::table;
+
+    const NAME: &'static str = VIAL_1_5ML_NAME;
+    const DESCRIPTION: &'static str = VIAL_1_5ML.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (VIAL_1_5ML_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        vial_1_5ml(context, conn)
     }
-
-    volumetric_container_models::table::builder()
-        .try_name(VIAL_1_5ML_NAME)
-        .expect("Failed to set volumetric container model name")
-        .try_description("Vial of 1.5 ml used for extracts storage")
-        .expect("Failed to set volumetric container model description")
-        .try_volume(0.0015_f32)
-        .expect("Failed to set volumetric container model volume")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
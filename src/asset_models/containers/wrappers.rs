@@ -1,13 +1,29 @@
 //! Submodule to initialize wrappers (packaging models) in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_packaging_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the coffee filter wrapper model.
+pub const COFFEE_FILTER_WRAPPER_NAME: &str = "Coffee Filter Wrapper";
+
+/// Definition of the coffee filter wrapper model.
+pub const COFFEE_FILTER_WRAPPER: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PackagingModels,
+    COFFEE_FILTER_WRAPPER_NAME,
+    "Coffee filters used to wrap sample in the field prior to storage in Falcon tubes",
+);
 
 /// Returns the coffee filter wrapper model, creating it if it does not exist.
 ///
@@ -23,11 +39,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let wrapper2 = coffee_filter_wrapper(&test_user, &mut conn).expect("Failed to create coffee filter wrapper model");
 /// assert_eq!(wrapper1, wrapper2);
 /// ```
-pub fn coffee_filter_wrapper<C>(
-    user: &User,
+pub fn coffee_filter_wrapper<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<packaging_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<packaging_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<packaging_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -36,32 +53,123 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<packaging_models::table, C>,
 {
-    const COFFEE_FILTER_WRAPPER_NAME: &str = "Coffee Filter Wrapper";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(
+                &context,
+                &reference_namespace,
+                COFFEE_FILTER_WRAPPER_NAME,
+                conn,
+            )
+        },
+        |conn| {
+            packaging_models::table::builder()
+                .try_name(COFFEE_FILTER_WRAPPER_NAME)
+                .map_err(TemplateError::attribute(COFFEE_FILTER_WRAPPER_NAME, "name"))?
+                .try_description(COFFEE_FILTER_WRAPPER.description)
+                .map_err(TemplateError::attribute(
+                    COFFEE_FILTER_WRAPPER_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, COFFEE_FILTER_WRAPPER_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(COFFEE_FILTER_WRAPPER_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the coffee filter wrapper model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`CoffeeFilterWrapper`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_coffee_filter_wrapper(&mut conn).expect("Failed to look up the coffee filter wrapper model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = coffee_filter_wrapper(&test_user, &mut conn).expect("Failed to create the coffee filter wrapper model");
+/// assert_eq!(find_coffee_filter_wrapper(&mut conn).expect("Failed to look up the coffee filter wrapper model"), Some(model));
+/// ```
+pub fn find_coffee_filter_wrapper<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<packaging_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<packaging_models::table, C>,
+{
+    CoffeeFilterWrapper::find(conn)
+}
+
+/// Returns the coffee filter wrapper model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`CoffeeFilterWrapper`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_coffee_filter_wrapper_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<packaging_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<packaging_models::table, C>,
+{
+    CoffeeFilterWrapper::find_in(namespace_name, conn)
+}
+
+/// Reference template of the coffee filter wrapper model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CoffeeFilterWrapper;
+
+impl AssetModelTemplate for CoffeeFilterWrapper {
+    type Table = packaging_models::table;
+
+    const NAME: &'static str = COFFEE_FILTER_WRAPPER_NAME;
+    const DESCRIPTION: &'static str = COFFEE_FILTER_WRAPPER.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (COFFEE_FILTER_WRAPPER_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        coffee_filter_wrapper(context, conn)
     }
-
-    packaging_models::table::builder()
-        .try_name(COFFEE_FILTER_WRAPPER_NAME)
-        .expect("Failed to set packaging model name")
-        .try_description(
-            "Coffee filters used to wrap sample in the field prior to storage in Falcon tubes",
-        )
-        .expect("Failed to set packaging model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,4 +1,8 @@
 //! Submodule defining `instruments`-related instances in the database.
+//!
+//! Only the centrifuge is mapped to an OBI term. The other instruments have no
+//! OBI term verified to describe them, so they are left unmapped, and exported
+//! without `skos:closeMatch`, rather than linked to a guessed identifier.
 
 pub mod ball_mill_machine;
 pub mod centrifuge;
@@ -10,12 +14,33 @@ pub mod pipettes;
 pub mod volume_measuring_device;
 pub mod weighing_scale;
 
-pub use ball_mill_machine::ball_mill_machine;
-pub use centrifuge::safelock_centrifuge;
-pub use freeze_dryer::freeze_dryer;
-pub use freezer::freezer;
-pub use phone::phone_model;
-pub use pipette_tips::{pipette_tip_200ul, pipette_tip_1000ul};
-pub use pipettes::{pipette_200ul, pipette_1000ul};
-pub use volume_measuring_device::volume_measuring_device_model;
-pub use weighing_scale::weighing_scale;
+pub use ball_mill_machine::{
+    BALL_MILL_MACHINE, BallMillMachine, ball_mill_machine, find_ball_mill_machine,
+    find_ball_mill_machine_in,
+};
+pub use centrifuge::{
+    SAFELOCK_CENTRIFUGE, SafelockCentrifuge, find_safelock_centrifuge, find_safelock_centrifuge_in,
+    safelock_centrifuge,
+};
+pub use freeze_dryer::{
+    FREEZE_DRYER, FreezeDryer, find_freeze_dryer, find_freeze_dryer_in, freeze_dryer,
+};
+pub use freezer::{FREEZER, Freezer, find_freezer, find_freezer_in, freezer};
+pub use phone::{PHONE_MODEL, PhoneModel, find_phone_model, find_phone_model_in, phone_model};
+pub use pipette_tips::{
+    PIPETTE_TIP_200UL, PIPETTE_TIP_1000UL, PipetteTip200ul, PipetteTip1000ul,
+    find_pipette_tip_200ul, find_pipette_tip_200ul_in, find_pipette_tip_1000ul,
+    find_pipette_tip_1000ul_in, pipette_tip_200ul, pipette_tip_1000ul,
+};
+pub use pipettes::{
+    PIPETTE_200UL, PIPETTE_1000UL, Pipette200ul, Pipette1000ul, find_pipette_200ul,
+    find_pipette_200ul_in, find_pipette_1000ul, find_pipette_1000ul_in, pipette_200ul,
+    pipette_1000ul,
+};
+pub use volume_measuring_device::{
+    VOLUME_MEASURING_DEVICE_MODEL, VolumeMeasuringDeviceModel, find_volume_measuring_device_model,
+    find_volume_measuring_device_model_in, volume_measuring_device_model,
+};
+pub use weighing_scale::{
+    WEIGHING_SCALE, WeighingScale, find_weighing_scale, find_weighing_scale_in, weighing_scale,
+};
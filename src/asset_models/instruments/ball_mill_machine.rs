@@ -1,19 +1,36 @@
 //! Submodule to initialize the ball mill machine model in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_ball_mill_machine_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the ball mill machine model.
+pub const BALL_MILL_MACHINE_NAME: &str = "Ball Mill Machine";
+
+/// Definition of the ball mill machine model.
+pub const BALL_MILL_MACHINE: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::BallMillMachineModels,
+    BALL_MILL_MACHINE_NAME,
+    "A Ball Mill Machine used to grind samples into powder.",
+);
 
 /// Returns the ball mill machine model, creating it if it does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user creating the model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -35,14 +52,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     .expect("Failed to create the ball mill machine model");
 /// assert_eq!(ball_mill_machine1, ball_mill_machine2);
 /// ```
-pub fn ball_mill_machine<C>(
-    user: &User,
+pub fn ball_mill_machine<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<ball_mill_machine_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<ball_mill_machine_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<ball_mill_machine_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -51,30 +66,123 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<ball_mill_machine_models::table, C>,
 {
-    const BALL_MILL_MACHINE_NAME: &str = "Ball Mill Machine";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(
+                &context,
+                &reference_namespace,
+                BALL_MILL_MACHINE_NAME,
+                conn,
+            )
+        },
+        |conn| {
+            ball_mill_machine_models::table::builder()
+                .try_name(BALL_MILL_MACHINE_NAME)
+                .map_err(TemplateError::attribute(BALL_MILL_MACHINE_NAME, "name"))?
+                .try_description(BALL_MILL_MACHINE.description)
+                .map_err(TemplateError::attribute(
+                    BALL_MILL_MACHINE_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, BALL_MILL_MACHINE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(BALL_MILL_MACHINE_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the ball mill machine model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`BallMillMachine`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_ball_mill_machine(&mut conn).expect("Failed to look up the ball mill machine model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = ball_mill_machine(&test_user, &mut conn).expect("Failed to create the ball mill machine model");
+/// assert_eq!(find_ball_mill_machine(&mut conn).expect("Failed to look up the ball mill machine model"), Some(model));
+/// ```
+pub fn find_ball_mill_machine<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<ball_mill_machine_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<ball_mill_machine_models::table, C>,
+{
+    BallMillMachine::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the ball mill machine model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`BallMillMachine`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_ball_mill_machine_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<ball_mill_machine_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<ball_mill_machine_models::table, C>,
+{
+    BallMillMachine::find_in(namespace_name, conn)
+}
+
+/// Reference template of the ball mill machine model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BallMillMachine;
+
+impl AssetModelTemplate for BallMillMachine {
+    type Table = ball_mill_machine_models::table;
+
+    const NAME: &'static str = BALL_MILL_MACHINE_NAME;
+    const DESCRIPTION: &'static str = BALL_MILL_MACHINE.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (BALL_MILL_MACHINE_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        ball_mill_machine(context, conn)
     }
-
-    ball_mill_machine_models::table::builder()
-        .try_name(BALL_MILL_MACHINE_NAME)
-        .expect("Failed to set ball mill machine model name")
-        .try_description("A Ball Mill Machine used to grind samples into powder.")
-        .expect("Failed to set ball mill machine model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
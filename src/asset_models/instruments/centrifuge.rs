@@ -1,19 +1,37 @@
 //! Submodule to initialize the centrifuge model in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, Ontology, OntologyTerm, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_centrifuge_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the centrifuge model.
+pub const CENTRIFUGE_NAME: &str = "Safelock Centrifuge";
+
+/// Definition of the centrifuge model.
+pub const SAFELOCK_CENTRIFUGE: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::CentrifugeModels,
+    CENTRIFUGE_NAME,
+    "Safelock centrifuge, used to precipitate solid material.",
+)
+.ontology(OntologyTerm::new(Ontology::Obi, "0400106"));
 
 /// Returns the centrifuge model, creating it if it does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user creating the model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -35,11 +53,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     .expect("Failed to create the centrifuge model");
 /// assert_eq!(centrifuge1, centrifuge2);
 /// ```
-pub fn safelock_centrifuge<C>(
-    user: &User,
+pub fn safelock_centrifuge<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<centrifuge_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<centrifuge_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<centrifuge_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -48,30 +67,112 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<centrifuge_models::table, C>,
 {
-    const CENTRIFUGE_NAME: &str = "Safelock Centrifuge";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, CENTRIFUGE_NAME, conn),
+        |conn| {
+            centrifuge_models::table::builder()
+                .try_name(CENTRIFUGE_NAME)
+                .map_err(TemplateError::attribute(CENTRIFUGE_NAME, "name"))?
+                .try_description(SAFELOCK_CENTRIFUGE.description)
+                .map_err(TemplateError::attribute(CENTRIFUGE_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, CENTRIFUGE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(CENTRIFUGE_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the centrifuge model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`SafelockCentrifuge`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_safelock_centrifuge(&mut conn).expect("Failed to look up the centrifuge model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = safelock_centrifuge(&test_user, &mut conn).expect("Failed to create the centrifuge model");
+/// assert_eq!(find_safelock_centrifuge(&mut conn).expect("Failed to look up the centrifuge model"), Some(model));
+/// ```
+pub fn find_safelock_centrifuge<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<centrifuge_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<centrifuge_models::table, C>,
+{
+    SafelockCentrifuge::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the centrifuge model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`SafelockCentrifuge`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_safelock_centrifuge_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<centrifuge_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<centrifuge_models::table, C>,
+{
+    SafelockCentrifuge::find_in(namespace_name, conn)
+}
+
+/// Reference template of the centrifuge model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SafelockCentrifuge;
+
+impl AssetModelTemplate for SafelockCentrifuge {
+    type Table = centrifuge_models::table;
+
+    const NAME: &'static str = CENTRIFUGE_NAME;
+    const DESCRIPTION: &'static str = SAFELOCK_CENTRIFUGE.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (CENTRIFUGE_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        safelock_centrifuge(context, conn)
     }
-
-    centrifuge_models::table::builder()
-        .try_name(CENTRIFUGE_NAME)
-        .expect("Failed to set centrifuge model name")
-        .try_description("Safelock centrifuge, used to precipitate solid material.")
-        .expect("Failed to set centrifuge model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
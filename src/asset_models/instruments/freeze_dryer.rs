@@ -1,13 +1,29 @@
 //! Submodule to initialize the freeze dryer in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_freeze_dryer_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the freeze dryer model.
+pub const FREEZE_DRYER_NAME: &str = "Freeze dryer";
+
+/// Definition of the freeze dryer model.
+pub const FREEZE_DRYER: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::FreezeDryerModels,
+    FREEZE_DRYER_NAME,
+    "A freeze dryer (or lyophilisator) used to sublimate water content of samples.",
+);
 
 /// Returns the freeze dryer.
 ///
@@ -18,7 +34,8 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the freeze dryer is being created.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection.
 ///
 /// # Errors
@@ -37,11 +54,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let freeze_dryer2 = freeze_dryer(&test_user, &mut conn).expect("Failed to create the freeze dryer model");
 /// assert_eq!(freeze_dryer1, freeze_dryer2);
 /// ```
-pub fn freeze_dryer<C>(
-    user: &User,
+pub fn freeze_dryer<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<freeze_dryer_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<freeze_dryer_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<freeze_dryer_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -50,32 +68,112 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<freeze_dryer_models::table, C>,
 {
-    const FREEZE_DRYER_NAME: &str = "Freeze dryer";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, FREEZE_DRYER_NAME, conn),
+        |conn| {
+            freeze_dryer_models::table::builder()
+                .try_name(FREEZE_DRYER_NAME)
+                .map_err(TemplateError::attribute(FREEZE_DRYER_NAME, "name"))?
+                .try_description(FREEZE_DRYER.description)
+                .map_err(TemplateError::attribute(FREEZE_DRYER_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, FREEZE_DRYER_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(FREEZE_DRYER_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the freeze dryer, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`FreezeDryer`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_freeze_dryer(&mut conn).expect("Failed to look up the freeze dryer").is_none());
+/// let test_user = user(&mut conn);
+/// let model = freeze_dryer(&test_user, &mut conn).expect("Failed to create the freeze dryer");
+/// assert_eq!(find_freeze_dryer(&mut conn).expect("Failed to look up the freeze dryer"), Some(model));
+/// ```
+pub fn find_freeze_dryer<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<freeze_dryer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freeze_dryer_models::table, C>,
+{
+    FreezeDryer::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the freeze dryer from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`FreezeDryer`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_freeze_dryer_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<freeze_dryer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<freeze_dryer_models::table, C>,
+{
+    FreezeDryer::find_in(namespace_name, conn)
+}
+
+/// Reference template of the freeze dryer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FreezeDryer;
+
+impl AssetModelTemplate for FreezeDryer {
+    type Table = freeze_dryer_models::table;
+
+    const NAME: &'static str = FREEZE_DRYER_NAME;
+    const DESCRIPTION: &'static str = FREEZE_DRYER.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (FREEZE_DRYER_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        freeze_dryer(context, conn)
     }
-
-    freeze_dryer_models::table::builder()
-        .try_name(FREEZE_DRYER_NAME)
-        .expect("Failed to set freeze dryer model name")
-        .try_description(
-            "A freeze dryer (or lyophilisator) used to sublimate water content of samples.",
-        )
-        .expect("Failed to set freeze dryer model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,13 +1,29 @@
 //! Submodule to initialize the freezer in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_freezer_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the -80°C freezer model.
+pub const FREEZER_NAME: &str = "Freezer -80°C";
+
+/// Definition of the -80°C freezer model.
+pub const FREEZER: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::FreezerModels,
+    FREEZER_NAME,
+    "A Freezer -80°C used for long-term storage of samples or freezing of samples prior to freeze-drying steps",
+);
 
 /// Returns the freezer.
 ///
@@ -18,7 +34,8 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the conical tube is being created.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection.
 ///
 /// # Errors
@@ -37,11 +54,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let freezer2 = freezer(&test_user, &mut conn).expect("Failed to create the freezer model");
 /// assert_eq!(freezer1, freezer2);
 /// ```
-pub fn freezer<C>(
-    user: &User,
+pub fn freezer<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<freezer_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<freezer_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<freezer_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -50,32 +68,112 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<freezer_models::table, C>,
 {
-    const FREEZER_NAME: &str = "Freezer -80°C";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, FREEZER_NAME, conn),
+        |conn| {
+            freezer_models::table::builder()
+                .try_name(FREEZER_NAME)
+                .map_err(TemplateError::attribute(FREEZER_NAME, "name"))?
+                .try_description(FREEZER.description)
+                .map_err(TemplateError::attribute(FREEZER_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, FREEZER_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(FREEZER_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the freezer, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`Freezer`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_freezer(&mut conn).expect("Failed to look up the freezer").is_none());
+/// let test_user = user(&mut conn);
+/// let model = freezer(&test_user, &mut conn).expect("Failed to create the freezer");
+/// assert_eq!(find_freezer(&mut conn).expect("Failed to look up the freezer"), Some(model));
+/// ```
+pub fn find_freezer<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<freezer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freezer_models::table, C>,
+{
+    Freezer::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the freezer from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`Freezer`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_freezer_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<freezer_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<freezer_models::table, C>,
+{
+    Freezer::find_in(namespace_name, conn)
+}
+
+/// Reference template of the freezer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Freezer;
+
+impl AssetModelTemplate for Freezer {
+    type Table = freezer_models::table;
+
+    const NAME: &'static str = FREEZER_NAME;
+    const DESCRIPTION: &'static str = FREEZER.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (FREEZER_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        freezer(context, conn)
     }
-
-    freezer_models::table::builder()
-        .try_name(FREEZER_NAME)
-        .expect("Failed to set freezer model name")
-        .try_description(
-            "A Freezer -80°C used for long-term storage of samples or freezing of samples prior to freeze-drying steps",
-        )
-        .expect("Failed to set freezer model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
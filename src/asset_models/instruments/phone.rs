@@ -1,14 +1,30 @@
 //! Submodule creating the instrument commercial product model for the Ball Mill
 //! instrument.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_phone_device_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the smartphone device model.
+pub const PHONE_NAME: &str = "Phone";
+
+/// Definition of the smartphone device model.
+pub const PHONE_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PhoneDeviceModels,
+    PHONE_NAME,
+    "A phone (smartphone) which may be used to take pictures or as a positioning device.",
+);
 
 /// Returns the smartphone device.
 ///
@@ -19,7 +35,8 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the smartphone is being created.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection.
 ///
 /// # Errors
@@ -38,11 +55,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let phone_model2 = phone_model(&test_user, &mut conn).expect("Failed to create the phone model");
 /// assert_eq!(phone_model1, phone_model2);
 /// ```
-pub fn phone_model<C>(
-    user: &User,
+pub fn phone_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<phone_device_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<phone_device_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<phone_device_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -51,32 +69,112 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<phone_device_models::table, C>,
 {
-    const PHONE_NAME: &str = "Phone";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, PHONE_NAME, conn),
+        |conn| {
+            phone_device_models::table::builder()
+                .try_name(PHONE_NAME)
+                .map_err(TemplateError::attribute(PHONE_NAME, "name"))?
+                .try_description(PHONE_MODEL.description)
+                .map_err(TemplateError::attribute(PHONE_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PHONE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PHONE_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the smartphone device, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`PhoneModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_phone_model(&mut conn).expect("Failed to look up the smartphone device").is_none());
+/// let test_user = user(&mut conn);
+/// let model = phone_model(&test_user, &mut conn).expect("Failed to create the smartphone device");
+/// assert_eq!(find_phone_model(&mut conn).expect("Failed to look up the smartphone device"), Some(model));
+/// ```
+pub fn find_phone_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<phone_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<phone_device_models::table, C>,
+{
+    PhoneModel::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the smartphone device from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`PhoneModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_phone_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<phone_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<phone_device_models::table, C>,
+{
+    PhoneModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the smartphone device.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PhoneModel;
+
+impl AssetModelTemplate for PhoneModel {
+    type Table = phone_device_models::table;
+
+    const NAME: &'static str = PHONE_NAME;
+    const DESCRIPTION: &'static str = PHONE_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PHONE_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        phone_model(context, conn)
     }
-
-    phone_device_models::table::builder()
-        .try_name(PHONE_NAME)
-        .expect("Failed to set phone model name")
-        .try_description(
-            "A phone (smartphone) which may be used to take pictures or as a positioning device.",
-        )
-        .expect("Failed to set phone model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,13 +1,29 @@
 //! Submodule to initialize pipette tip models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_pipette_tip_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the 200μl pipette tip model.
+pub const PIPETTE_TIP_200UL_NAME: &str = "Pipette Tip 200μl";
+
+/// Definition of the 200μl pipette tip model.
+pub const PIPETTE_TIP_200UL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PipetteTipModels,
+    PIPETTE_TIP_200UL_NAME,
+    "A 200μl pipette tip used to manipulate and transfer liquids when adapted to a pipette",
+);
 
 /// Returns the 200μl pipette tip model, creating it if it does not exist.
 ///
@@ -23,11 +39,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let pipette_tip_200ul_2 = pipette_tip_200ul(&test_user, &mut conn).expect("Failed to create the 200μl pipette tip model");
 /// assert_eq!(pipette_tip_200ul_1, pipette_tip_200ul_2);
 /// ```
-pub fn pipette_tip_200ul<C>(
-    user: &User,
+pub fn pipette_tip_200ul<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<pipette_tip_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<pipette_tip_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<pipette_tip_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -36,36 +53,137 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<pipette_tip_models::table, C>,
 {
-    const PIPETTE_TIP_200UL_NAME: &str = "Pipette Tip 200μl";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(
+                &context,
+                &reference_namespace,
+                PIPETTE_TIP_200UL_NAME,
+                conn,
+            )
+        },
+        |conn| {
+            pipette_tip_models::table::builder()
+                .try_name(PIPETTE_TIP_200UL_NAME)
+                .map_err(TemplateError::attribute(PIPETTE_TIP_200UL_NAME, "name"))?
+                .try_description(PIPETTE_TIP_200UL.description)
+                .map_err(TemplateError::attribute(
+                    PIPETTE_TIP_200UL_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PIPETTE_TIP_200UL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PIPETTE_TIP_200UL_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the 200μl pipette tip model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`PipetteTip200ul`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_pipette_tip_200ul(&mut conn).expect("Failed to look up the 200μl pipette tip model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = pipette_tip_200ul(&test_user, &mut conn).expect("Failed to create the 200μl pipette tip model");
+/// assert_eq!(find_pipette_tip_200ul(&mut conn).expect("Failed to look up the 200μl pipette tip model"), Some(model));
+/// ```
+pub fn find_pipette_tip_200ul<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<pipette_tip_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+{
+    PipetteTip200ul::find(conn)
+}
+
+/// Returns the 200μl pipette tip model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`PipetteTip200ul`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_pipette_tip_200ul_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<pipette_tip_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+{
+    PipetteTip200ul::find_in(namespace_name, conn)
+}
+
+/// Reference template of the 200μl pipette tip model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PipetteTip200ul;
+
+impl AssetModelTemplate for PipetteTip200ul {
+    type Table = pipette_tip_models::table;
+
+    const NAME: &'static str = PIPETTE_TIP_200UL_NAME;
+    const DESCRIPTION: &'static str = PIPETTE_TIP_200UL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PIPETTE_TIP_200UL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        pipette_tip_200ul(context, conn)
     }
-
-    pipette_tip_models::table::builder()
-        .try_name(PIPETTE_TIP_200UL_NAME)
-        .expect("Failed to set pipette tip model name")
-        .try_description(
-            "A 200μl pipette tip used to manipulate and transfer liquids when adapted to a pipette",
-        )
-        .expect("Failed to set pipette tip model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
 
+/// Name of the 1ml pipette tip model.
+pub const PIPETTE_TIP_1000UL_NAME: &str = "Pipette Tip 1ml";
+
+/// Definition of the 1ml pipette tip model.
+pub const PIPETTE_TIP_1000UL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PipetteTipModels,
+    PIPETTE_TIP_1000UL_NAME,
+    "A 1000μl pipette tip used to manipulate and transfer liquids when adapted to a pipette",
+);
+
 /// Returns the 1ml pipette tip model, creating it if it does not exist.
 ///
 /// # Example
@@ -80,11 +198,12 @@ where
 /// let pipette_tip_1000ul_2 = pipette_tip_1000ul(&test_user, &mut conn).expect("Failed to create the 1ml pipette tip model");
 /// assert_eq!(pipette_tip_1000ul_1, pipette_tip_1000ul_2);
 /// ```
-pub fn pipette_tip_1000ul<C>(
-    user: &User,
+pub fn pipette_tip_1000ul<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<pipette_tip_models::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<pipette_tip_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<pipette_tip_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -93,32 +212,122 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<pipette_tip_models::table, C>,
 {
-    const PIPETTE_TIP_1000UL_NAME: &str = "Pipette Tip 1ml";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(
+                &context,
+                &reference_namespace,
+                PIPETTE_TIP_1000UL_NAME,
+                conn,
+            )
+        },
+        |conn| {
+            pipette_tip_models::table::builder()
+                .try_name(PIPETTE_TIP_1000UL_NAME)
+                .map_err(TemplateError::attribute(PIPETTE_TIP_1000UL_NAME, "name"))?
+                .try_description(PIPETTE_TIP_1000UL.description)
+                .map_err(TemplateError::attribute(
+                    PIPETTE_TIP_1000UL_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PIPETTE_TIP_1000UL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PIPETTE_TIP_1000UL_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the 1ml pipette tip model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`PipetteTip1000ul`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_pipette_tip_1000ul(&mut conn).expect("Failed to look up the 1ml pipette tip model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = pipette_tip_1000ul(&test_user, &mut conn).expect("Failed to create the 1ml pipette tip model");
+/// assert_eq!(find_pipette_tip_1000ul(&mut conn).expect("Failed to look up the 1ml pipette tip model"), Some(model));
+/// ```
+pub fn find_pipette_tip_1000ul<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<pipette_tip_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+{
+    PipetteTip1000ul::find(conn)
+}
+
+/// Returns the 1ml pipette tip model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`PipetteTip1000ul`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_pipette_tip_1000ul_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<pipette_tip_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+{
+    PipetteTip1000ul::find_in(namespace_name, conn)
+}
+
+/// Reference template of the 1ml pipette tip model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PipetteTip1000ul;
+
+impl AssetModelTemplate for PipetteTip1000ul {
+    type Table = pipette_tip_models::table;
+
+    const NAME: &'static str = PIPETTE_TIP_1000UL_NAME;
+    const DESCRIPTION: &'static str = PIPETTE_TIP_1000UL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PIPETTE_TIP_1000UL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        pipette_tip_1000ul(context, conn)
     }
-
-    pipette_tip_models::table::builder()
-        .try_name(PIPETTE_TIP_1000UL_NAME)
-        .expect("Failed to set pipette tip model name")
-        .try_description(
-            "A 1000μl pipette tip used to manipulate and transfer liquids when adapted to a pipette",
-        )
-        .expect("Failed to set pipette tip model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,13 +1,29 @@
 //! Submodule to initialize pipette-related models in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volume_measuring_device_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the 200μl pipette model.
+pub const PIPETTE_200UL_NAME: &str = "Pipette 200μl";
+
+/// Definition of the 200μl pipette model.
+pub const PIPETTE_200UL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumeMeasuringDeviceModels,
+    PIPETTE_200UL_NAME,
+    "A pipette used to manipulate liquids (needs to be equipped with a pipette tip).",
+);
 
 /// Returns the 200μl pipette model, creating it if it does not exist.
 ///
@@ -23,14 +39,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let pipette_200ul_2 = pipette_200ul(&test_user, &mut conn).expect("Failed to create the 200μl pipette model");
 /// assert_eq!(pipette_200ul_1, pipette_200ul_2);
 /// ```
-pub fn pipette_200ul<C>(
-    user: &User,
+pub fn pipette_200ul<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volume_measuring_device_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volume_measuring_device_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volume_measuring_device_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -39,36 +53,128 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volume_measuring_device_models::table, C>,
 {
-    const PIPETTE_200UL_NAME: &str = "Pipette 200μl";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, PIPETTE_200UL_NAME, conn)
+        },
+        |conn| {
+            volume_measuring_device_models::table::builder()
+                .try_name(PIPETTE_200UL_NAME)
+                .map_err(TemplateError::attribute(PIPETTE_200UL_NAME, "name"))?
+                .try_description(PIPETTE_200UL.description)
+                .map_err(TemplateError::attribute(PIPETTE_200UL_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PIPETTE_200UL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PIPETTE_200UL_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the 200μl pipette model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`Pipette200ul`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_pipette_200ul(&mut conn).expect("Failed to look up the 200μl pipette model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = pipette_200ul(&test_user, &mut conn).expect("Failed to create the 200μl pipette model");
+/// assert_eq!(find_pipette_200ul(&mut conn).expect("Failed to look up the 200μl pipette model"), Some(model));
+/// ```
+pub fn find_pipette_200ul<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volume_measuring_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+{
+    Pipette200ul::find(conn)
+}
+
+/// Returns the 200μl pipette model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`Pipette200ul`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_pipette_200ul_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volume_measuring_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+{
+    Pipette200ul::find_in(namespace_name, conn)
+}
+
+/// Reference template of the 200μl pipette model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Pipette200ul;
+
+impl AssetModelTemplate for Pipette200ul {
+    type Table = volume_measuring_device_models::table;
+
+    const NAME: &'static str = PIPETTE_200UL_NAME;
+    const DESCRIPTION: &'static str = PIPETTE_200UL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PIPETTE_200UL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        pipette_200ul(context, conn)
     }
-
-    volume_measuring_device_models::table::builder()
-        .try_name(PIPETTE_200UL_NAME)
-        .expect("Failed to set pipette model name")
-        .try_description(
-            "A pipette used to manipulate liquids (needs to be equipped with a pipette tip).",
-        )
-        .expect("Failed to set pipette model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
 
+/// Name of the 1000µl pipette model.
+pub const PIPETTE_1000UL_NAME: &str = "Pipette 1000µl";
+
+/// Definition of the 1000µl pipette model.
+pub const PIPETTE_1000UL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumeMeasuringDeviceModels,
+    PIPETTE_1000UL_NAME,
+    "A pipette used to manipulate liquids (needs to be equipped with a pipette tip).",
+);
+
 /// Returns the 1000µl pipette model, creating it if it does not exist.
 ///
 /// # Example
@@ -83,14 +189,12 @@ where
 /// let pipette_1000ul_2 = pipette_1000ul(&test_user, &mut conn).expect("Failed to create the 1000µl pipette model");
 /// assert_eq!(pipette_1000ul_1, pipette_1000ul_2);
 /// ```
-pub fn pipette_1000ul<C>(
-    user: &User,
+pub fn pipette_1000ul<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volume_measuring_device_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volume_measuring_device_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volume_measuring_device_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -99,32 +203,114 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volume_measuring_device_models::table, C>,
 {
-    const PIPETTE_1000UL_NAME: &str = "Pipette 1000µl";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, PIPETTE_1000UL_NAME, conn)
+        },
+        |conn| {
+            volume_measuring_device_models::table::builder()
+                .try_name(PIPETTE_1000UL_NAME)
+                .map_err(TemplateError::attribute(PIPETTE_1000UL_NAME, "name"))?
+                .try_description(PIPETTE_1000UL.description)
+                .map_err(TemplateError::attribute(PIPETTE_1000UL_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PIPETTE_1000UL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PIPETTE_1000UL_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the 1000µl pipette model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`Pipette1000ul`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_pipette_1000ul(&mut conn).expect("Failed to look up the 1000µl pipette model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = pipette_1000ul(&test_user, &mut conn).expect("Failed to create the 1000µl pipette model");
+/// assert_eq!(find_pipette_1000ul(&mut conn).expect("Failed to look up the 1000µl pipette model"), Some(model));
+/// ```
+pub fn find_pipette_1000ul<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volume_measuring_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+{
+    Pipette1000ul::find(conn)
+}
+
+/// Returns the 1000µl pipette model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`Pipette1000ul`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_pipette_1000ul_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volume_measuring_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+{
+    Pipette1000ul::find_in(namespace_name, conn)
+}
+
+/// Reference template of the 1000µl pipette model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Pipette1000ul;
+
+impl AssetModelTemplate for Pipette1000ul {
+    type Table = volume_measuring_device_models::table;
+
+    const NAME: &'static str = PIPETTE_1000UL_NAME;
+    const DESCRIPTION: &'static str = PIPETTE_1000UL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PIPETTE_1000UL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        pipette_1000ul(context, conn)
     }
-
-    volume_measuring_device_models::table::builder()
-        .try_name(PIPETTE_1000UL_NAME)
-        .expect("Failed to set pipette model name")
-        .try_description(
-            "A pipette used to manipulate liquids (needs to be equipped with a pipette tip).",
-        )
-        .expect("Failed to set pipette model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,14 +1,30 @@
 //! Submodule creating the instrument commercial product model for the Measuring
 //! devices model.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_volume_measuring_device_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+/// Name of the volume measuring device model.
+pub const VOLUME_MEASURING_DEVICE_NAME: &str = "Volume Measuring Device";
+
+/// Definition of the volume measuring device model.
+pub const VOLUME_MEASURING_DEVICE_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::VolumeMeasuringDeviceModels,
+    VOLUME_MEASURING_DEVICE_NAME,
+    "A generic volume measuring device",
+);
+
 /// Returns the volume measuring device model instance.
 ///
 /// # Implementation Details
@@ -18,7 +34,8 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the conical tube is being created.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection.
 ///
 /// # Errors
@@ -39,14 +56,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     .expect("Failed to create the volume measuring device model");
 /// assert_eq!(volume_measuring_device_model1, volume_measuring_device_model2);
 /// ```
-pub fn volume_measuring_device_model<C>(
-    user: &User,
+pub fn volume_measuring_device_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<volume_measuring_device_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<volume_measuring_device_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<volume_measuring_device_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -55,30 +70,128 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<volume_measuring_device_models::table, C>,
 {
-    const VOLUME_MEASURING_DEVICE_NAME: &str = "Volume Measuring Device";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(
+                &context,
+                &reference_namespace,
+                VOLUME_MEASURING_DEVICE_NAME,
+                conn,
+            )
+        },
+        |conn| {
+            volume_measuring_device_models::table::builder()
+                .try_name(VOLUME_MEASURING_DEVICE_NAME)
+                .map_err(TemplateError::attribute(
+                    VOLUME_MEASURING_DEVICE_NAME,
+                    "name",
+                ))?
+                .try_description(VOLUME_MEASURING_DEVICE_MODEL.description)
+                .map_err(TemplateError::attribute(
+                    VOLUME_MEASURING_DEVICE_NAME,
+                    "description",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, VOLUME_MEASURING_DEVICE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(VOLUME_MEASURING_DEVICE_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the volume measuring device model instance, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on
+/// [`VolumeMeasuringDeviceModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_volume_measuring_device_model(&mut conn).expect("Failed to look up the volume measuring device model instance").is_none());
+/// let test_user = user(&mut conn);
+/// let model = volume_measuring_device_model(&test_user, &mut conn).expect("Failed to create the volume measuring device model instance");
+/// assert_eq!(find_volume_measuring_device_model(&mut conn).expect("Failed to look up the volume measuring device model instance"), Some(model));
+/// ```
+pub fn find_volume_measuring_device_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<volume_measuring_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+{
+    VolumeMeasuringDeviceModel::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the volume measuring device model instance from the provided
+/// namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on
+/// [`VolumeMeasuringDeviceModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_volume_measuring_device_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<volume_measuring_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+{
+    VolumeMeasuringDeviceModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the volume measuring device model instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VolumeMeasuringDeviceModel;
+
+impl AssetModelTemplate for VolumeMeasuringDeviceModel {
+    type Table = volume_measuring_device_models::table;
+
+    const NAME: &'static str = VOLUME_MEASURING_DEVICE_NAME;
+    const DESCRIPTION: &'static str = VOLUME_MEASURING_DEVICE_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (VOLUME_MEASURING_DEVICE_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        volume_measuring_device_model(context, conn)
     }
-
-    volume_measuring_device_models::table::builder()
-        .try_name(VOLUME_MEASURING_DEVICE_NAME)
-        .expect("Failed to set volume measuring device model name")
-        .try_description("A generic volume measuring device")
-        .expect("Failed to set volume measuring device model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,14 +1,30 @@
 //! Submodule creating the instrument commercial product model for the Pipette
 //! 200 instrument.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
 use aps::aps_weighing_device_models::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+/// Name of the weighing scale model.
+pub const WEIGHING_SCALE_NAME: &str = "Weighing Scale";
+
+/// Definition of the weighing scale model.
+pub const WEIGHING_SCALE: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::WeighingDeviceModels,
+    WEIGHING_SCALE_NAME,
+    "A weighing scale used to measure the amount of samples.",
+);
+
 /// Returns the weighing scale.
 ///
 /// # Implementation Details
@@ -18,7 +34,8 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the conical tube is being created.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection.
 ///
 /// # Errors
@@ -37,14 +54,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let weighing_scale2 = weighing_scale(&test_user, &mut conn).expect("Failed to create the weighing scale model");
 /// assert_eq!(weighing_scale1, weighing_scale2);
 /// ```
-pub fn weighing_scale<C>(
-    user: &User,
+pub fn weighing_scale<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<weighing_device_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<weighing_device_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<weighing_device_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -53,30 +68,114 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<weighing_device_models::table, C>,
 {
-    const WEIGHING_SCALE_NAME: &str = "Weighing Scale";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, WEIGHING_SCALE_NAME, conn)
+        },
+        |conn| {
+            weighing_device_models::table::builder()
+                .try_name(WEIGHING_SCALE_NAME)
+                .map_err(TemplateError::attribute(WEIGHING_SCALE_NAME, "name"))?
+                .try_description(WEIGHING_SCALE.description)
+                .map_err(TemplateError::attribute(WEIGHING_SCALE_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, WEIGHING_SCALE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(WEIGHING_SCALE_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the weighing scale, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`WeighingScale`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_weighing_scale(&mut conn).expect("Failed to look up the weighing scale").is_none());
+/// let test_user = user(&mut conn);
+/// let model = weighing_scale(&test_user, &mut conn).expect("Failed to create the weighing scale");
+/// assert_eq!(find_weighing_scale(&mut conn).expect("Failed to look up the weighing scale"), Some(model));
+/// ```
+pub fn find_weighing_scale<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<weighing_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<weighing_device_models::table, C>,
+{
+    WeighingScale::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the weighing scale from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`WeighingScale`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_weighing_scale_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<weighing_device_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<weighing_device_models::table, C>,
+{
+    WeighingScale::find_in(namespace_name, conn)
+}
+
+/// Reference template of the weighing scale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WeighingScale;
+
+impl AssetModelTemplate for WeighingScale {
+    type Table = weighing_device_models::table;
+
+    const NAME: &'static str = WEIGHING_SCALE_NAME;
+    const DESCRIPTION: &'static str = WEIGHING_SCALE.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (WEIGHING_SCALE_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        weighing_scale(context, conn)
     }
-
-    weighing_device_models::table::builder()
-        .try_name(WEIGHING_SCALE_NAME)
-        .expect("Failed to set weighing scale model name")
-        .try_description("A weighing scale used to measure the amount of samples.")
-        .expect("Failed to set weighing scale model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,20 +1,37 @@
 //! Submodule to initialize the `markers` in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the marker model for cardboard arrows.
+pub const MARKER_ARROW: &str = "Marker Arrow";
+
+/// Definition of the marker model for cardboard arrows.
+pub const MARKER_ARROW_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PhysicalAssetModels,
+    MARKER_ARROW,
+    "Marker arrow to highlight in a photograph a subject of interest.",
+);
 
 /// Returns the marker model for cardboard arrows, creating it if it does not
 /// exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user who is creating the marker model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -34,14 +51,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let marker_arrow_model2 = marker_arrow_model(&test_user, &mut conn).expect("Failed to create the marker arrow model");
 /// assert_eq!(marker_arrow_model1, marker_arrow_model2);
 /// ```
-pub fn marker_arrow_model<C>(
-    user: &User,
+pub fn marker_arrow_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<physical_asset_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<physical_asset_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -50,30 +65,113 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<physical_asset_models::table, C>,
 {
-    const MARKER_ARROW: &str = "Marker Arrow";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, MARKER_ARROW, conn),
+        |conn| {
+            physical_asset_models::table::builder()
+                .try_name(MARKER_ARROW)
+                .map_err(TemplateError::attribute(MARKER_ARROW, "name"))?
+                .try_description(MARKER_ARROW_MODEL.description)
+                .map_err(TemplateError::attribute(MARKER_ARROW, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, MARKER_ARROW)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(MARKER_ARROW))
+        },
+        conn,
+    )
+}
+
+/// Returns the marker model for cardboard arrows, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`MarkerArrowModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_marker_arrow_model(&mut conn).expect("Failed to look up the marker model for cardboard arrows").is_none());
+/// let test_user = user(&mut conn);
+/// let model = marker_arrow_model(&test_user, &mut conn).expect("Failed to create the marker model for cardboard arrows");
+/// assert_eq!(find_marker_arrow_model(&mut conn).expect("Failed to look up the marker model for cardboard arrows"), Some(model));
+/// ```
+pub fn find_marker_arrow_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    MarkerArrowModel::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the marker model for cardboard arrows from the provided namespace,
+/// if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`MarkerArrowModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_marker_arrow_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    MarkerArrowModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the marker model for cardboard arrows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MarkerArrowModel;
+
+impl AssetModelTemplate for MarkerArrowModel {
+    type Table = physical_asset_models::table;
+
+    const NAME: &'static str = MARKER_ARROW;
+    const DESCRIPTION: &'static str = MARKER_ARROW_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (MARKER_ARROW,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        marker_arrow_model(context, conn)
     }
-
-    physical_asset_models::table::builder()
-        .try_name(MARKER_ARROW)
-        .expect("Failed to set physical asset model name")
-        .try_description("Marker arrow to highlight in a photograph a subject of interest.")
-        .expect("Failed to set physical asset model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,57 +1,363 @@
 //! Submodule to initialize the `organisms` in the database.
 
-use core_structures::{
-    OrganismModel, SampleModel, User,
-    tables::insertables::{AssetModelSettable, SampleModelSettable},
-};
-use diesel::{OptionalExtension, PgConnection};
-use web_common_traits::database::{DispatchableInsertableVariant, Insertable};
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, Ontology, OntologyTerm, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
+use crate::prelude::reference_namespace;
+use crate::template::{AssetModelTemplate, SampleModelTemplate};
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_ownables::*;
+use aps::aps_sample_models::*;
+use aps::aps_sample_source_models::*;
+use aps::aps_users::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
 
-/// Returns the organism trackable.
+/// Name of the organism sample source model.
+pub const ORGANISM_NAME: &str = "Organism";
+
+/// Definition of the organism sample source model.
+pub const ORGANISM_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::SampleSourceModels,
+    ORGANISM_NAME,
+    "Organisms used in laboratory procedures",
+)
+.ontology(OntologyTerm::new(Ontology::Obi, "0100026"));
+
+/// Name of the organism sample model.
+pub const ORGANISM_SAMPLE_NAME: &str = "Organism Sample";
+
+/// Definition of the organism sample model.
+pub const ORGANISM_SAMPLE_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::SampleModels,
+    ORGANISM_SAMPLE_NAME,
+    "Samples used in laboratory procedures",
+)
+.sample_source(&ORGANISM_MODEL)
+.ontology(OntologyTerm::new(Ontology::Obi, "0000747"));
+
+/// Returns the organism sample source model, creating it if it does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the organism is being created.
-/// * `conn` - The database connection.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
+/// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
 ///
 /// * If the connection to the database fails.
-pub fn organism_model(user: &User, conn: &mut PgConnection) -> anyhow::Result<OrganismModel> {
-    const ORGANISM: &str = "Organism";
+/// * If the insertion fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let organism_model1 = organism_model(&test_user, &mut conn).expect("Failed to create the organism model");
+/// let organism_model2 = organism_model(&test_user, &mut conn).expect("Failed to create the organism model");
+/// assert_eq!(organism_model1, organism_model2);
+/// ```
+pub fn organism_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<NestedModel<sample_source_models::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<sample_source_models::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, ORGANISM_NAME, conn),
+        |conn| {
+            sample_source_models::table::builder()
+                .try_name(ORGANISM_NAME)
+                .map_err(TemplateError::attribute(ORGANISM_NAME, "name"))?
+                .try_description(ORGANISM_MODEL.description)
+                .map_err(TemplateError::attribute(ORGANISM_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, ORGANISM_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(ORGANISM_NAME))
+        },
+        conn,
+    )
+}
 
-    if let Some(existing_organism) = OrganismModel::from_name(ORGANISM, conn).optional()? {
-        return Ok(existing_organism);
-    }
+/// Returns the organism sample source model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`OrganismModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_organism_model(&mut conn).expect("Failed to look up the organism model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = organism_model(&test_user, &mut conn).expect("Failed to create the organism model");
+/// assert_eq!(find_organism_model(&mut conn).expect("Failed to look up the organism model"), Some(model));
+/// ```
+pub fn find_organism_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_source_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    OrganismModel::find(conn)
+}
+
+/// Returns the organism sample source model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`OrganismModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_organism_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_source_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    OrganismModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the organism sample source model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OrganismModel;
+
+impl AssetModelTemplate for OrganismModel {
+    type Table = sample_source_models::table;
 
-    Ok(OrganismModel::new()
-        .name(ORGANISM)?
-        .description("Organisms used in laboratory procedures")?
-        .created_by(user)?
-        .insert(user.id, conn)?)
+    const NAME: &'static str = ORGANISM_NAME;
+    const DESCRIPTION: &'static str = ORGANISM_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        organism_model(context, conn)
+    }
 }
 
-/// Returns the sample trackable.
+/// Returns the organism sample model, creating it and its organism sample source
+/// model if they do not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the sample is being created.
-/// * `conn` - The database connection.
+/// * `context` - The namespace and users to create the models with, or simply
+///   the creating user.
+/// * `conn` - The database connection to use for the insertions.
 ///
 /// # Errors
 ///
 /// * If the connection to the database fails.
-pub fn organism_sample_model(user: &User, conn: &mut PgConnection) -> anyhow::Result<SampleModel> {
-    const SAMPLE: &str = "Organism Sample";
+/// * If any of the insertions fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let sample1 = organism_sample_model(&test_user, &mut conn).expect("Failed to create the organism sample model");
+/// let sample2 = organism_sample_model(&test_user, &mut conn).expect("Failed to create the organism sample model");
+/// assert_eq!(sample1, sample2);
+/// assert_eq!(sample1, OrganismSampleModel::get_or_create(&test_user, &mut conn).expect("Failed to create the organism sample model"));
+/// assert!(find_organism_model(&mut conn).expect("Failed to look up the organism model").is_some());
+/// ```
+pub fn organism_sample_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<NestedModel<sample_models::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<sample_models::table>: Insert<C>,
+    TableBuilder<sample_source_models::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| {
+            load_active_reference_model(&context, &reference_namespace, ORGANISM_SAMPLE_NAME, conn)
+        },
+        |conn| {
+            let organism = organism_model(context, conn)?;
+            sample_models::table::builder()
+                .try_name(ORGANISM_SAMPLE_NAME)
+                .map_err(TemplateError::attribute(ORGANISM_SAMPLE_NAME, "name"))?
+                .try_description(ORGANISM_SAMPLE_MODEL.description)
+                .map_err(TemplateError::attribute(
+                    ORGANISM_SAMPLE_NAME,
+                    "description",
+                ))?
+                .try_sample_source_model_id(organism.get_column::<sample_source_models::id>())
+                .map_err(TemplateError::attribute(
+                    ORGANISM_SAMPLE_NAME,
+                    "sample_source_model_id",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, ORGANISM_SAMPLE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(ORGANISM_SAMPLE_NAME))
+        },
+        conn,
+    )
+}
 
-    if let Some(existing_sample) = SampleModel::from_name(SAMPLE, conn).optional()? {
-        return Ok(existing_sample);
-    }
+/// Returns the organism sample model, if it exists.
+///
+/// Shorthand for [`SampleModelTemplate::find`] on [`OrganismSampleModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_organism_sample_model(&mut conn).expect("Failed to look up the organism sample model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = organism_sample_model(&test_user, &mut conn).expect("Failed to create the organism sample model");
+/// assert_eq!(find_organism_sample_model(&mut conn).expect("Failed to look up the organism sample model"), Some(model));
+/// ```
+pub fn find_organism_sample_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+{
+    OrganismSampleModel::find(conn)
+}
+
+/// Returns the organism sample model from the provided namespace, if it exists.
+///
+/// Shorthand for [`SampleModelTemplate::find_in`] on [`OrganismSampleModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_organism_sample_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+{
+    OrganismSampleModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the organism sample model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OrganismSampleModel;
 
-    Ok(SampleModel::new()
-        .name(SAMPLE)?
-        .description("Samples used in laboratory procedures")?
-        .sample_source_model(organism_model(user, conn)?)?
-        .created_by(user)?
-        .insert(user.id, conn)?)
+impl SampleModelTemplate for OrganismSampleModel {
+    type Source = OrganismModel;
+
+    const NAME: &'static str = ORGANISM_SAMPLE_NAME;
+    const DESCRIPTION: &'static str = ORGANISM_SAMPLE_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<sample_models::table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<sample_models::table>: Insert<C>,
+        TableBuilder<sample_source_models::table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_models::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_source_models::table, C>,
+    {
+        organism_sample_model(context, conn)
+    }
 }
@@ -1,13 +1,29 @@
 //! Submodule to initialize the `panels` in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the panel model.
+pub const PANEL_NAME: &str = "Panel";
+
+/// Definition of the panel model.
+pub const PANEL_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PhysicalAssetModels,
+    PANEL_NAME,
+    "Panel for documenting organisms, typically used in botanical gardens.",
+);
 
 /// Returns the panel model, creating it if it does not exist.
 ///
@@ -23,14 +39,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let panel_model2 = panel_model(&test_user, &mut conn).expect("Failed to create the panel model");
 /// assert_eq!(panel_model1, panel_model2);
 /// ```
-pub fn panel_model<C>(
-    user: &User,
+pub fn panel_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<physical_asset_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<physical_asset_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -39,30 +53,112 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<physical_asset_models::table, C>,
 {
-    const PANEL_NAME: &str = "Panel";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, PANEL_NAME, conn),
+        |conn| {
+            physical_asset_models::table::builder()
+                .try_name(PANEL_NAME)
+                .map_err(TemplateError::attribute(PANEL_NAME, "name"))?
+                .try_description(PANEL_MODEL.description)
+                .map_err(TemplateError::attribute(PANEL_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PANEL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PANEL_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the panel model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`PanelModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_panel_model(&mut conn).expect("Failed to look up the panel model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = panel_model(&test_user, &mut conn).expect("Failed to create the panel model");
+/// assert_eq!(find_panel_model(&mut conn).expect("Failed to look up the panel model"), Some(model));
+/// ```
+pub fn find_panel_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    PanelModel::find(conn)
+}
+
+/// Returns the panel model from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`PanelModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_panel_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    PanelModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the panel model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PanelModel;
+
+impl AssetModelTemplate for PanelModel {
+    type Table = physical_asset_models::table;
+
+    const NAME: &'static str = PANEL_NAME;
+    const DESCRIPTION: &'static str = PANEL_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PANEL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        panel_model(context, conn)
     }
-
-    physical_asset_models::table::builder()
-        .try_name(PANEL_NAME)
-        .expect("Failed to set physical asset model name")
-        .try_description("Panel for documenting organisms, typically used in botanical gardens.")
-        .expect("Failed to set physical asset model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,19 +1,40 @@
 //! Submodule to initialize the `photographs` in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_digital_asset_models::*;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the photograph asset model.
+pub const PHOTOGRAPH_NAME: &str = "Photograph";
+
+/// MIME type of the photograph asset model.
+const PHOTOGRAPH_MODEL_MIME_TYPE: &str = "image/jpeg";
+
+/// Definition of the photograph asset model.
+pub const PHOTOGRAPH_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::DigitalAssetModels,
+    PHOTOGRAPH_NAME,
+    "Photograph for documenting organisms and their habitats",
+)
+.mime_type(PHOTOGRAPH_MODEL_MIME_TYPE);
 
 /// Returns the photograph asset model, creating it if it does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user who is creating the digital asset model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -33,14 +54,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let photograph_model2 = photograph_model(&test_user, &mut conn).expect("Failed to create the photograph model");
 /// assert_eq!(photograph_model1, photograph_model2);
 /// ```
-pub fn photograph_model<C>(
-    user: &User,
+pub fn photograph_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<digital_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<digital_asset_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<digital_asset_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -49,32 +68,115 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<digital_asset_models::table, C>,
 {
-    const PHOTOGRAPH_NAME: &str = "Photograph";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, PHOTOGRAPH_NAME, conn),
+        |conn| {
+            digital_asset_models::table::builder()
+                .try_name(PHOTOGRAPH_NAME)
+                .map_err(TemplateError::attribute(PHOTOGRAPH_NAME, "name"))?
+                .try_description(PHOTOGRAPH_MODEL.description)
+                .map_err(TemplateError::attribute(PHOTOGRAPH_NAME, "description"))?
+                .try_mime_type(PHOTOGRAPH_MODEL_MIME_TYPE)
+                .map_err(TemplateError::attribute(PHOTOGRAPH_NAME, "mime_type"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, PHOTOGRAPH_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(PHOTOGRAPH_NAME))
+        },
+        conn,
+    )
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the photograph asset model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`PhotographModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_photograph_model(&mut conn).expect("Failed to look up the photograph asset model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = photograph_model(&test_user, &mut conn).expect("Failed to create the photograph asset model");
+/// assert_eq!(find_photograph_model(&mut conn).expect("Failed to look up the photograph asset model"), Some(model));
+/// ```
+pub fn find_photograph_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<digital_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<digital_asset_models::table, C>,
+{
+    PhotographModel::find(conn)
+}
+
+/// Returns the photograph asset model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`PhotographModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_photograph_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<digital_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<digital_asset_models::table, C>,
+{
+    PhotographModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the photograph asset model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PhotographModel;
+
+impl AssetModelTemplate for PhotographModel {
+    type Table = digital_asset_models::table;
+
+    const NAME: &'static str = PHOTOGRAPH_NAME;
+    const DESCRIPTION: &'static str = PHOTOGRAPH_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (PHOTOGRAPH_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        photograph_model(context, conn)
     }
-
-    digital_asset_models::table::builder()
-        .try_name(PHOTOGRAPH_NAME)
-        .expect("Failed to set digital asset model name")
-        .try_description("Photograph for documenting organisms and their habitats")
-        .expect("Failed to set digital asset model description")
-        .try_mime_type("image/jpeg")
-        .expect("Failed to set digital asset model mime type")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
@@ -1,21 +1,37 @@
 //! Submodule to initialize the `ppe` in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_personal_protective_equipment_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::BuilderError;
+use diesel::Connection;
 use diesel_builders::{TableBuilder, prelude::*};
 
+/// Name of the PPE model for gloves.
+pub const GLOVES_NAME: &str = "Latex Gloves";
+
+/// Definition of the PPE model for gloves.
+pub const GLOVE_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PersonalProtectiveEquipmentModels,
+    GLOVES_NAME,
+    "Latex or nitrile gloves used for personal protection.",
+);
+
 /// Returns the PPE model for gloves, creating it if it does not
 /// exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user who is creating the PPE model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -36,14 +52,12 @@ use diesel_builders::{TableBuilder, prelude::*};
 /// let glove_model2 = glove_model(&test_user, &mut conn).expect("Failed to create the glove model");
 /// assert_eq!(glove_model1, glove_model2);
 /// ```
-pub fn glove_model<C>(
-    user: &User,
+pub fn glove_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<personal_protective_equipment_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<personal_protective_equipment_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<personal_protective_equipment_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -52,30 +66,112 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<personal_protective_equipment_models::table, C>,
 {
-    const GLOVES_NAME: &str = "Latex Gloves";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, GLOVES_NAME, conn),
+        |conn| {
+            personal_protective_equipment_models::table::builder()
+                .try_name(GLOVES_NAME)
+                .map_err(TemplateError::attribute(GLOVES_NAME, "name"))?
+                .try_description(GLOVE_MODEL.description)
+                .map_err(TemplateError::attribute(GLOVES_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, GLOVES_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(GLOVES_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the PPE model for gloves, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`GloveModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_glove_model(&mut conn).expect("Failed to look up the PPE model for gloves").is_none());
+/// let test_user = user(&mut conn);
+/// let model = glove_model(&test_user, &mut conn).expect("Failed to create the PPE model for gloves");
+/// assert_eq!(find_glove_model(&mut conn).expect("Failed to look up the PPE model for gloves"), Some(model));
+/// ```
+pub fn find_glove_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<personal_protective_equipment_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<personal_protective_equipment_models::table, C>,
+{
+    GloveModel::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the PPE model for gloves from the provided namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`GloveModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_glove_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<personal_protective_equipment_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<personal_protective_equipment_models::table, C>,
+{
+    GloveModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the PPE model for gloves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GloveModel;
+
+impl AssetModelTemplate for GloveModel {
+    type Table = personal_protective_equipment_models::table;
+
+    const NAME: &'static str = GLOVES_NAME;
+    const DESCRIPTION: &'static str = GLOVE_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (GLOVES_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        glove_model(context, conn)
     }
-
-    personal_protective_equipment_models::table::builder()
-        .try_name(GLOVES_NAME)
-        .expect("Failed to set physical asset model name")
-        .try_description("Latex or nitrile gloves used for personal protection.")
-        .expect("Failed to set physical asset model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
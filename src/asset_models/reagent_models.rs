@@ -1,16 +1,24 @@
 //! Submodule to initialize reagent models in the database.
 
-use crate::prelude::reference_namespace;
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::ReagentProperties;
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::{find_reference_model, get_or_insert};
+use crate::prelude::{REFERENCE_NAMESPACE_NAME, reference_namespace};
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
-use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
+use aps::aps_reagent_models::*;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
 
 /// Returns a reagent model, creating it if it does not exist.
 ///
+/// The chemical properties are stored in dedicated columns, so that reagents
+/// can be queried e.g. by CAS registry number.
+///
 /// # Example
 ///
 /// ```rust
@@ -19,10 +27,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let mut conn = aps_git_conn();
 ///
 /// let test_user = user(&mut conn);
+/// let acetonitrile = ReagentProperties::new(99.9, "75-05-8", "C2H3N");
 /// let reagent_model1 = reagent_model(
 ///     &test_user,
 ///     "Template Reagent",
 ///     "Template reagent description.",
+///     &acetonitrile,
 ///     &mut conn,
 /// )
 /// .expect("Failed to create reagent model");
@@ -30,60 +40,145 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     &test_user,
 ///     "Template Reagent",
 ///     "Template reagent description.",
+///     &acetonitrile,
 ///     &mut conn,
 /// )
 /// .expect("Failed to create reagent model");
 /// assert_eq!(reagent_model1, reagent_model2);
 /// ```
-pub fn reagent_model<C>(
-    user: &User,
+pub fn reagent_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     name: &str,
     description: &str,
+    properties: &ReagentProperties<'_>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<reagent_models::table>, TemplateError>
 where
-    TableBuilder<physical_asset_models::table>: Insert<C>,
+    C: Connection,
+    TableBuilder<reagent_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
     (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    ): LoadNestedFirst<physical_asset_models::table, C>,
+    ): LoadNestedFirst<reagent_models::table, C>,
 {
-    let reference_namespace = reference_namespace(user, conn)?;
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, name, conn),
+        |conn| {
+            reagent_models::table::builder()
+                .try_name(name)
+                .map_err(TemplateError::attribute(name, "name"))?
+                .try_description(description)
+                .map_err(TemplateError::attribute(name, "description"))?
+                .try_purity(properties.purity)
+                .map_err(TemplateError::attribute(name, "purity"))?
+                .try_cas_code(properties.cas_code)
+                .map_err(TemplateError::attribute(name, "cas_code"))?
+                .try_molecular_formula(properties.molecular_formula)
+                .map_err(TemplateError::attribute(name, "molecular_formula"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, name)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(name))
+        },
+        conn,
+    )
+}
 
-    if let Ok(existing) = <(
+/// Returns the reagent model with the provided name, if it exists.
+///
+/// This is the lookup counterpart of [`reagent_model`], for reagents without a
+/// template of their own, see
+/// [`AssetModelTemplate::find`](crate::template::AssetModelTemplate::find).
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_reagent_model("Template Reagent", &mut conn).expect("Failed to look up reagent model").is_none());
+/// let test_user = user(&mut conn);
+/// let acetonitrile = ReagentProperties::new(99.9, "75-05-8", "C2H3N");
+/// let model = reagent_model(&test_user, "Template Reagent", "Template reagent description.", &acetonitrile, &mut conn)
+///     .expect("Failed to create reagent model");
+/// assert_eq!(find_reagent_model("Template Reagent", &mut conn).expect("Failed to look up reagent model"), Some(model));
+/// ```
+pub fn find_reagent_model<C>(
+    name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
-        (reference_namespace.get_column::<namespaces::id>(), (name,)),
-        conn,
-    ) {
-        return Ok(existing);
-    }
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    find_reagent_model_in(REFERENCE_NAMESPACE_NAME, name, conn)
+}
 
-    physical_asset_models::table::builder()
-        .try_name(name)
-        .expect("Failed to set reagent model name")
-        .try_description(description)
-        .expect("Failed to set reagent model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
+/// Returns the reagent model with the provided name from the provided
+/// namespace, if it exists.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `name` - The name of the reagent model.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_reagent_model_in<C>(
+    namespace_name: &str,
+    name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    find_reference_model(namespace_name, name, conn)
 }
 
+#[cfg(feature = "reagent-inventory")]
+pub mod commercial;
 pub mod distilled_water;
 pub mod ethanol;
 pub mod formic_acid;
 pub mod liquid_nitrogen;
 pub mod methanol;
-pub use distilled_water::distilled_water;
-pub use ethanol::absolute_ethanol;
-pub use formic_acid::formic_acid;
-pub use liquid_nitrogen::liquid_nitrogen;
-pub use methanol::methanol_hplc;
+#[cfg(feature = "reagent-inventory")]
+pub use commercial::{commercial_reagent_lot, commercial_reagent_model, register_reagent_bottle};
+pub use distilled_water::{
+    DISTILLED_WATER, DistilledWater, distilled_water, find_distilled_water, find_distilled_water_in,
+};
+pub use ethanol::{
+    ABSOLUTE_ETHANOL, AbsoluteEthanol, absolute_ethanol, find_absolute_ethanol,
+    find_absolute_ethanol_in,
+};
+pub use formic_acid::{
+    FORMIC_ACID, FormicAcid, find_formic_acid, find_formic_acid_in, formic_acid,
+};
+pub use liquid_nitrogen::{
+    LIQUID_NITROGEN, LiquidNitrogen, find_liquid_nitrogen, find_liquid_nitrogen_in, liquid_nitrogen,
+};
+pub use methanol::{
+    METHANOL_HPLC, MethanolHplc, find_methanol_hplc, find_methanol_hplc_in, methanol_hplc,
+};
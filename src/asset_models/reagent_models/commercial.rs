@@ -0,0 +1,312 @@
+//! Submodule to register commercial reagent products, their lots and the
+//! physical reagent bottles of those lots in the database.
+//!
+//! Unlike the reference templates, these rows describe what a lab actually
+//! purchased, so their names are built from the vendor, catalog and lot
+//! numbers provided by the caller, and they are stored in the namespace of the
+//! lab named by the context rather than in the shared reference namespace.
+//!
+//! The `commercial_reagent_models`, `commercial_reagent_lots` and `reagents`
+//! tables are not part of the `aps` schema yet, so this module is only built
+//! with the `reagent-inventory` feature, to be enabled along with an `aps`
+//! revision providing them.
+
+use chrono::NaiveDate;
+
+use crate::context::TemplateContext;
+use crate::errors::TemplateError;
+use crate::lookup::{get_or_insert, load_reference_model};
+use crate::prelude::{REFERENCE_NAMESPACE_NAME, reference_namespace};
+use aps::aps_commercial_reagent_lots::*;
+use aps::aps_commercial_reagent_models::*;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_ownables::*;
+use aps::aps_reagent_models::*;
+use aps::aps_reagents::*;
+use aps::aps_users::*;
+use aps::aps_volumetric_container_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Returns the commercial product of the provided reagent model sold by the
+/// provided vendor under the provided catalog number, creating it if it does
+/// not exist.
+///
+/// # Arguments
+///
+/// * `context` - The lab namespace and users to create the product with,
+///   which must not name the reference namespace.
+/// * `reagent` - The reagent model the product is an instance of.
+/// * `vendor` - The name of the vendor selling the product.
+/// * `catalog_number` - The catalog number of the product at the vendor.
+/// * `conn` - The database connection to use for the insertion.
+///
+/// # Errors
+///
+/// * If the context names the reference namespace.
+/// * If the connection to the database fails.
+/// * If any of the attributes is rejected by the schema validation.
+/// * If the insertion fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let lab = TemplateContext::new(&test_user).namespace_name("lab_inventory");
+/// let methanol = methanol_hplc(&test_user, &mut conn).expect("Failed to create methanol model");
+/// let product1 = commercial_reagent_model(lab, &methanol, "Sigma-Aldrich", "34860", &mut conn)
+///     .expect("Failed to create the commercial methanol model");
+/// let product2 = commercial_reagent_model(lab, &methanol, "Sigma-Aldrich", "34860", &mut conn)
+///     .expect("Failed to create the commercial methanol model");
+/// assert_eq!(product1, product2);
+/// assert!(matches!(
+///     commercial_reagent_model(&test_user, &methanol, "Sigma-Aldrich", "34860", &mut conn),
+///     Err(TemplateError::SharedNamespace { .. })
+/// ));
+/// ```
+pub fn commercial_reagent_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    reagent: &NestedModel<reagent_models::table>,
+    vendor: &str,
+    catalog_number: &str,
+    conn: &mut C,
+) -> Result<NestedModel<commercial_reagent_models::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<commercial_reagent_models::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<commercial_reagent_models::table, C>,
+{
+    let context = context.into();
+    let name = format!("{vendor} {catalog_number}");
+    let namespace = inventory_namespace(context, &name, conn)?;
+    get_or_insert(
+        |conn| load_reference_model(&namespace, &name, conn),
+        |conn| {
+            commercial_reagent_models::table::builder()
+                .try_name(name.as_str())
+                .map_err(TemplateError::attribute(&name, "name"))?
+                .try_description(format!(
+                    "{}, sold by {vendor} as {catalog_number}",
+                    reagent.get_column::<namespaced_ownables::name>()
+                ))
+                .map_err(TemplateError::attribute(&name, "description"))?
+                .try_reagent_model_id(reagent.get_column::<reagent_models::id>())
+                .map_err(TemplateError::attribute(&name, "reagent_model_id"))?
+                .try_vendor(vendor)
+                .map_err(TemplateError::attribute(&name, "vendor"))?
+                .try_catalog_number(catalog_number)
+                .map_err(TemplateError::attribute(&name, "catalog_number"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(namespace.get_column::<namespaces::id>())
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(&name))
+        },
+        conn,
+    )
+}
+
+/// Returns the lot with the provided lot number of the provided commercial
+/// reagent product, creating it if it does not exist.
+///
+/// # Arguments
+///
+/// * `context` - The lab namespace and users to create the lot with,
+///   which must not name the reference namespace.
+/// * `product` - The commercial reagent product the lot belongs to.
+/// * `lot_number` - The lot number printed by the vendor.
+/// * `expiry_date` - The date after which the lot must not be used.
+/// * `conn` - The database connection to use for the insertion.
+///
+/// # Errors
+///
+/// * If the context names the reference namespace.
+/// * If the connection to the database fails.
+/// * If any of the attributes is rejected by the schema validation.
+/// * If the insertion fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use chrono::NaiveDate;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let lab = TemplateContext::new(&test_user).namespace_name("lab_inventory");
+/// let methanol = methanol_hplc(&test_user, &mut conn).expect("Failed to create methanol model");
+/// let product = commercial_reagent_model(lab, &methanol, "Sigma-Aldrich", "34860", &mut conn)
+///     .expect("Failed to create the commercial methanol model");
+/// let expiry_date = NaiveDate::from_ymd_opt(2027, 6, 30).unwrap();
+/// let lot1 = commercial_reagent_lot(lab, &product, "SHBL1234", expiry_date, &mut conn)
+///     .expect("Failed to create the methanol lot");
+/// let lot2 = commercial_reagent_lot(lab, &product, "SHBL1234", expiry_date, &mut conn)
+///     .expect("Failed to create the methanol lot");
+/// assert_eq!(lot1, lot2);
+/// ```
+pub fn commercial_reagent_lot<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    product: &NestedModel<commercial_reagent_models::table>,
+    lot_number: &str,
+    expiry_date: NaiveDate,
+    conn: &mut C,
+) -> Result<NestedModel<commercial_reagent_lots::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<commercial_reagent_lots::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<commercial_reagent_lots::table, C>,
+{
+    let context = context.into();
+    let name = format!(
+        "{} lot {lot_number}",
+        product.get_column::<namespaced_ownables::name>()
+    );
+    let namespace = inventory_namespace(context, &name, conn)?;
+    get_or_insert(
+        |conn| load_reference_model(&namespace, &name, conn),
+        |conn| {
+            commercial_reagent_lots::table::builder()
+                .try_name(name.as_str())
+                .map_err(TemplateError::attribute(&name, "name"))?
+                .try_description(format!("Lot {lot_number}, expiring on {expiry_date}"))
+                .map_err(TemplateError::attribute(&name, "description"))?
+                .try_commercial_reagent_model_id(
+                    product.get_column::<commercial_reagent_models::id>(),
+                )
+                .map_err(TemplateError::attribute(
+                    &name,
+                    "commercial_reagent_model_id",
+                ))?
+                .try_lot(lot_number)
+                .map_err(TemplateError::attribute(&name, "lot"))?
+                .try_expiry_date(expiry_date)
+                .map_err(TemplateError::attribute(&name, "expiry_date"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(namespace.get_column::<namespaces::id>())
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(&name))
+        },
+        conn,
+    )
+}
+
+/// Registers a physical bottle of reagent from the provided lot, stored in a
+/// container of the provided model, e.g. [`bottle_1l`](crate::asset_models::bottle_1l).
+///
+/// Every call registers a new bottle, so that the bottles of a lot can be
+/// traced individually.
+///
+/// # Arguments
+///
+/// * `context` - The lab namespace and users to register the bottle with,
+///   which must not name the reference namespace.
+/// * `lot` - The lot the reagent comes from.
+/// * `container` - The model of the container holding the reagent.
+/// * `label` - The label identifying the bottle, e.g. its barcode.
+/// * `conn` - The database connection to use for the insertion.
+///
+/// # Errors
+///
+/// * If the context names the reference namespace.
+/// * If the connection to the database fails.
+/// * If any of the attributes is rejected by the schema validation.
+/// * If the insertion fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use chrono::NaiveDate;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let lab = TemplateContext::new(&test_user).namespace_name("lab_inventory");
+/// let methanol = methanol_hplc(&test_user, &mut conn).expect("Failed to create methanol model");
+/// let product = commercial_reagent_model(lab, &methanol, "Sigma-Aldrich", "34860", &mut conn)
+///     .expect("Failed to create the commercial methanol model");
+/// let expiry_date = NaiveDate::from_ymd_opt(2027, 6, 30).unwrap();
+/// let lot = commercial_reagent_lot(lab, &product, "SHBL1234", expiry_date, &mut conn)
+///     .expect("Failed to create the methanol lot");
+/// let bottle = bottle_1l(&test_user, &mut conn).expect("Failed to create bottle model");
+/// let first = register_reagent_bottle(lab, &lot, &bottle, "MeOH-0001", &mut conn)
+///     .expect("Failed to register the methanol bottle");
+/// let second = register_reagent_bottle(lab, &lot, &bottle, "MeOH-0002", &mut conn)
+///     .expect("Failed to register the methanol bottle");
+/// assert_ne!(first, second);
+/// ```
+pub fn register_reagent_bottle<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    lot: &NestedModel<commercial_reagent_lots::table>,
+    container: &NestedModel<volumetric_container_models::table>,
+    label: &str,
+    conn: &mut C,
+) -> Result<NestedModel<reagents::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<reagents::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+{
+    let context = context.into();
+    let namespace = inventory_namespace(context, label, conn)?;
+    reagents::table::builder()
+        .try_name(label)
+        .map_err(TemplateError::attribute(label, "name"))?
+        .try_description(format!(
+            "Bottle of {} in a {}",
+            lot.get_column::<namespaced_ownables::name>(),
+            container.get_column::<namespaced_ownables::name>()
+        ))
+        .map_err(TemplateError::attribute(label, "description"))?
+        .try_commercial_reagent_lot_id(lot.get_column::<commercial_reagent_lots::id>())
+        .map_err(TemplateError::attribute(label, "commercial_reagent_lot_id"))?
+        .try_container_model_id(container.get_column::<volumetric_container_models::id>())
+        .map_err(TemplateError::attribute(label, "container_model_id"))?
+        .creator_id(context.creator.get_column::<users::id>())
+        .editor_id(context.editor.get_column::<users::id>())
+        .owner_id(context.owner.get_column::<users::id>())
+        .namespace_id(namespace.get_column::<namespaces::id>())
+        .insert_nested(conn)
+        .map_err(TemplateError::insert(label))
+}
+
+/// Returns the lab namespace named by the provided context, creating it if it
+/// does not exist, and refusing the reference namespace shared by all labs.
+fn inventory_namespace<C>(
+    context: TemplateContext<'_>,
+    template: &str,
+    conn: &mut C,
+) -> Result<NestedModel<namespaces::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+{
+    if context.namespace_name == REFERENCE_NAMESPACE_NAME {
+        return Err(TemplateError::SharedNamespace {
+            template: template.to_owned(),
+            namespace: REFERENCE_NAMESPACE_NAME,
+        });
+    }
+    reference_namespace(context, conn)
+}
@@ -1,11 +1,36 @@
 //! Submodule defining functions to initialize `distilled_water` reagent models.
 
 use super::reagent_model;
+use crate::context::TemplateContext;
+use crate::definitions::{
+    ModelTable, Ontology, OntologyTerm, ReagentProperties, TemplateDefinition,
+};
+use crate::errors::TemplateError;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
-use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use aps::aps_reagent_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the distilled water reagent model.
+pub const DISTILLED_WATER_NAME: &str = "Distilled water";
+
+/// Description of the distilled water reagent model.
+pub const DISTILLED_WATER_DESCRIPTION: &str = "Distilled water, pure";
+
+/// Chemical properties of the distilled water reagent model.
+pub const DISTILLED_WATER_PROPERTIES: ReagentProperties<'static> =
+    ReagentProperties::new(100.0, "7732-18-5", "H2O");
+
+/// Definition of the distilled water reagent model.
+pub const DISTILLED_WATER: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ReagentModels,
+    DISTILLED_WATER_NAME,
+    DISTILLED_WATER_DESCRIPTION,
+)
+.reagent(DISTILLED_WATER_PROPERTIES)
+.ontology(OntologyTerm::new(Ontology::Chebi, "15377"));
 
 /// Returns the distilled water reagent model, creating it if it does not exist.
 ///
@@ -23,28 +48,114 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     distilled_water(&test_user, &mut conn).expect("Failed to create distilled water model");
 /// assert_eq!(distilled_water_model1, distilled_water_model2);
 /// ```
-pub fn distilled_water<C>(
-    user: &User,
+pub fn distilled_water<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<reagent_models::table>, TemplateError>
 where
-    TableBuilder<physical_asset_models::table>: Insert<C>,
+    C: Connection,
+    TableBuilder<reagent_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
     (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    ): LoadNestedFirst<physical_asset_models::table, C>,
+    ): LoadNestedFirst<reagent_models::table, C>,
 {
-    const DISTILLED_WATER_NAME: &str = "Distilled water";
-    const DISTILLED_WATER_DESCRIPTION: &str = "Distilled water, pure";
     reagent_model(
-        user,
+        context,
         DISTILLED_WATER_NAME,
         DISTILLED_WATER_DESCRIPTION,
+        &DISTILLED_WATER_PROPERTIES,
         conn,
     )
 }
+
+/// Returns the distilled water reagent model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`DistilledWater`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_distilled_water(&mut conn).expect("Failed to look up the distilled water reagent model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = distilled_water(&test_user, &mut conn).expect("Failed to create the distilled water reagent model");
+/// assert_eq!(find_distilled_water(&mut conn).expect("Failed to look up the distilled water reagent model"), Some(model));
+/// ```
+pub fn find_distilled_water<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    DistilledWater::find(conn)
+}
+
+/// Returns the distilled water reagent model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`DistilledWater`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_distilled_water_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    DistilledWater::find_in(namespace_name, conn)
+}
+
+/// Reference template of the distilled water reagent model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DistilledWater;
+
+impl AssetModelTemplate for DistilledWater {
+    type Table = reagent_models::table;
+
+    const NAME: &'static str = DISTILLED_WATER_NAME;
+    const DESCRIPTION: &'static str = DISTILLED_WATER.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        distilled_water(context, conn)
+    }
+}
@@ -1,11 +1,33 @@
 //! Submodule defining functions to initialize `ethanol` reagent models.
 
 use super::reagent_model;
+use crate::context::TemplateContext;
+use crate::definitions::{
+    ModelTable, Ontology, OntologyTerm, ReagentProperties, TemplateDefinition,
+};
+use crate::errors::TemplateError;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
-use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use aps::aps_reagent_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the absolute ethanol reagent model.
+pub const ETHANOL_NAME: &str = "Absolute Ethanol, >= 95%";
+
+/// Description of the absolute ethanol reagent model.
+pub const ETHANOL_DESCRIPTION: &str = "Absolute Ethanol, >= 95%, with 5% isopropanol";
+
+/// Chemical properties of the absolute ethanol reagent model.
+pub const ETHANOL_PROPERTIES: ReagentProperties<'static> =
+    ReagentProperties::new(95.0, "64-17-5", "C2H6O");
+
+/// Definition of the absolute ethanol reagent model.
+pub const ABSOLUTE_ETHANOL: TemplateDefinition =
+    TemplateDefinition::new(ModelTable::ReagentModels, ETHANOL_NAME, ETHANOL_DESCRIPTION)
+        .reagent(ETHANOL_PROPERTIES)
+        .ontology(OntologyTerm::new(Ontology::Chebi, "16236"));
 
 /// Returns the absolute ethanol reagent model, creating it if it does not
 /// exist.
@@ -24,23 +46,114 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     absolute_ethanol(&test_user, &mut conn).expect("Failed to create ethanol model");
 /// assert_eq!(ethanol_model1, ethanol_model2);
 /// ```
-pub fn absolute_ethanol<C>(
-    user: &User,
+pub fn absolute_ethanol<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<reagent_models::table>, TemplateError>
 where
-    TableBuilder<physical_asset_models::table>: Insert<C>,
+    C: Connection,
+    TableBuilder<reagent_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
     (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    ): LoadNestedFirst<physical_asset_models::table, C>,
+    ): LoadNestedFirst<reagent_models::table, C>,
 {
-    const ETHANOL_NAME: &str = "Absolute Ethanol, >= 95%";
-    const ETHANOL_DESCRIPTION: &str = "Absolute Ethanol, >= 95%, with 5% isopropanol";
-    reagent_model(user, ETHANOL_NAME, ETHANOL_DESCRIPTION, conn)
+    reagent_model(
+        context,
+        ETHANOL_NAME,
+        ETHANOL_DESCRIPTION,
+        &ETHANOL_PROPERTIES,
+        conn,
+    )
+}
+
+/// Returns the absolute ethanol reagent model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`AbsoluteEthanol`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_absolute_ethanol(&mut conn).expect("Failed to look up the absolute ethanol reagent model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = absolute_ethanol(&test_user, &mut conn).expect("Failed to create the absolute ethanol reagent model");
+/// assert_eq!(find_absolute_ethanol(&mut conn).expect("Failed to look up the absolute ethanol reagent model"), Some(model));
+/// ```
+pub fn find_absolute_ethanol<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    AbsoluteEthanol::find(conn)
+}
+
+/// Returns the absolute ethanol reagent model from the provided namespace, if
+/// it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`AbsoluteEthanol`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_absolute_ethanol_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    AbsoluteEthanol::find_in(namespace_name, conn)
+}
+
+/// Reference template of the absolute ethanol reagent model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AbsoluteEthanol;
+
+impl AssetModelTemplate for AbsoluteEthanol {
+    type Table = reagent_models::table;
+
+    const NAME: &'static str = ETHANOL_NAME;
+    const DESCRIPTION: &'static str = ABSOLUTE_ETHANOL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        absolute_ethanol(context, conn)
+    }
 }
@@ -1,11 +1,36 @@
 //! Submodule defining functions to initialize `formic_acid` reagent models.
 
 use super::reagent_model;
+use crate::context::TemplateContext;
+use crate::definitions::{
+    ModelTable, Ontology, OntologyTerm, ReagentProperties, TemplateDefinition,
+};
+use crate::errors::TemplateError;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
-use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use aps::aps_reagent_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the formic acid reagent model.
+pub const FORMIC_ACID_NAME: &str = "Formic acid";
+
+/// Description of the formic acid reagent model.
+pub const FORMIC_ACID_DESCRIPTION: &str = "Formic acid, pure";
+
+/// Chemical properties of the formic acid reagent model.
+pub const FORMIC_ACID_PROPERTIES: ReagentProperties<'static> =
+    ReagentProperties::new(100.0, "64-18-6", "CH2O2");
+
+/// Definition of the formic acid reagent model.
+pub const FORMIC_ACID: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ReagentModels,
+    FORMIC_ACID_NAME,
+    FORMIC_ACID_DESCRIPTION,
+)
+.reagent(FORMIC_ACID_PROPERTIES)
+.ontology(OntologyTerm::new(Ontology::Chebi, "30751"));
 
 /// Returns the formic acid reagent model, creating it if it does not exist.
 ///
@@ -23,23 +48,114 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     formic_acid(&test_user, &mut conn).expect("Failed to create formic acid model");
 /// assert_eq!(formic_acid_model1, formic_acid_model2);
 /// ```
-pub fn formic_acid<C>(
-    user: &User,
+pub fn formic_acid<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<reagent_models::table>, TemplateError>
 where
-    TableBuilder<physical_asset_models::table>: Insert<C>,
+    C: Connection,
+    TableBuilder<reagent_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
     (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    ): LoadNestedFirst<physical_asset_models::table, C>,
+    ): LoadNestedFirst<reagent_models::table, C>,
 {
-    const FORMIC_ACID_NAME: &str = "Formic acid";
-    const FORMIC_ACID_DESCRIPTION: &str = "Formic acid, pure";
-    reagent_model(user, FORMIC_ACID_NAME, FORMIC_ACID_DESCRIPTION, conn)
+    reagent_model(
+        context,
+        FORMIC_ACID_NAME,
+        FORMIC_ACID_DESCRIPTION,
+        &FORMIC_ACID_PROPERTIES,
+        conn,
+    )
+}
+
+/// Returns the formic acid reagent model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`FormicAcid`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_formic_acid(&mut conn).expect("Failed to look up the formic acid reagent model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = formic_acid(&test_user, &mut conn).expect("Failed to create the formic acid reagent model");
+/// assert_eq!(find_formic_acid(&mut conn).expect("Failed to look up the formic acid reagent model"), Some(model));
+/// ```
+pub fn find_formic_acid<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    FormicAcid::find(conn)
+}
+
+/// Returns the formic acid reagent model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`FormicAcid`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_formic_acid_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    FormicAcid::find_in(namespace_name, conn)
+}
+
+/// Reference template of the formic acid reagent model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FormicAcid;
+
+impl AssetModelTemplate for FormicAcid {
+    type Table = reagent_models::table;
+
+    const NAME: &'static str = FORMIC_ACID_NAME;
+    const DESCRIPTION: &'static str = FORMIC_ACID.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        formic_acid(context, conn)
+    }
 }
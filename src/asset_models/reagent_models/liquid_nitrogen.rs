@@ -1,11 +1,36 @@
 //! Submodule defining functions to initialize `liquid_nitrogen` reagent models.
 
 use super::reagent_model;
+use crate::context::TemplateContext;
+use crate::definitions::{
+    ModelTable, Ontology, OntologyTerm, ReagentProperties, TemplateDefinition,
+};
+use crate::errors::TemplateError;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
-use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use aps::aps_reagent_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the liquid nitrogen reagent model.
+pub const LIQUID_NITROGEN_NAME: &str = "Liquid nitrogen";
+
+/// Description of the liquid nitrogen reagent model.
+pub const LIQUID_NITROGEN_DESCRIPTION: &str = "Liquid nitrogen, pure";
+
+/// Chemical properties of the liquid nitrogen reagent model.
+pub const LIQUID_NITROGEN_PROPERTIES: ReagentProperties<'static> =
+    ReagentProperties::new(100.0, "7727-37-9", "N2");
+
+/// Definition of the liquid nitrogen reagent model.
+pub const LIQUID_NITROGEN: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ReagentModels,
+    LIQUID_NITROGEN_NAME,
+    LIQUID_NITROGEN_DESCRIPTION,
+)
+.reagent(LIQUID_NITROGEN_PROPERTIES)
+.ontology(OntologyTerm::new(Ontology::Chebi, "17997"));
 
 /// Returns the liquid nitrogen reagent model, creating it if it does not
 /// exist.
@@ -24,28 +49,114 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     liquid_nitrogen(&test_user, &mut conn).expect("Failed to create liquid nitrogen model");
 /// assert_eq!(liquid_nitrogen_model1, liquid_nitrogen_model2);
 /// ```
-pub fn liquid_nitrogen<C>(
-    user: &User,
+pub fn liquid_nitrogen<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<reagent_models::table>, TemplateError>
 where
-    TableBuilder<physical_asset_models::table>: Insert<C>,
+    C: Connection,
+    TableBuilder<reagent_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
     (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    ): LoadNestedFirst<physical_asset_models::table, C>,
+    ): LoadNestedFirst<reagent_models::table, C>,
 {
-    const LIQUID_NITROGEN_NAME: &str = "Liquid nitrogen";
-    const LIQUID_NITROGEN_DESCRIPTION: &str = "Liquid nitrogen, pure";
     reagent_model(
-        user,
+        context,
         LIQUID_NITROGEN_NAME,
         LIQUID_NITROGEN_DESCRIPTION,
+        &LIQUID_NITROGEN_PROPERTIES,
         conn,
     )
 }
+
+/// Returns the liquid nitrogen reagent model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`LiquidNitrogen`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_liquid_nitrogen(&mut conn).expect("Failed to look up the liquid nitrogen reagent model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = liquid_nitrogen(&test_user, &mut conn).expect("Failed to create the liquid nitrogen reagent model");
+/// assert_eq!(find_liquid_nitrogen(&mut conn).expect("Failed to look up the liquid nitrogen reagent model"), Some(model));
+/// ```
+pub fn find_liquid_nitrogen<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    LiquidNitrogen::find(conn)
+}
+
+/// Returns the liquid nitrogen reagent model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`LiquidNitrogen`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_liquid_nitrogen_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    LiquidNitrogen::find_in(namespace_name, conn)
+}
+
+/// Reference template of the liquid nitrogen reagent model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LiquidNitrogen;
+
+impl AssetModelTemplate for LiquidNitrogen {
+    type Table = reagent_models::table;
+
+    const NAME: &'static str = LIQUID_NITROGEN_NAME;
+    const DESCRIPTION: &'static str = LIQUID_NITROGEN.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        liquid_nitrogen(context, conn)
+    }
+}
@@ -1,11 +1,36 @@
 //! Submodule defining functions to initialize `methanol` reagent models.
 
 use super::reagent_model;
+use crate::context::TemplateContext;
+use crate::definitions::{
+    ModelTable, Ontology, OntologyTerm, ReagentProperties, TemplateDefinition,
+};
+use crate::errors::TemplateError;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
-use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use aps::aps_reagent_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the methanol reagent model.
+pub const METHANOL_NAME: &str = "Methanol, >= 99.8%, HPLC grade";
+
+/// Description of the methanol reagent model.
+pub const METHANOL_DESCRIPTION: &str = "Methanol, >= 99.8%, HPLC grade";
+
+/// Chemical properties of the methanol reagent model.
+pub const METHANOL_PROPERTIES: ReagentProperties<'static> =
+    ReagentProperties::new(99.8, "67-56-1", "CH4O");
+
+/// Definition of the methanol reagent model.
+pub const METHANOL_HPLC: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::ReagentModels,
+    METHANOL_NAME,
+    METHANOL_DESCRIPTION,
+)
+.reagent(METHANOL_PROPERTIES)
+.ontology(OntologyTerm::new(Ontology::Chebi, "17790"));
 
 /// Returns the methanol reagent model, creating it if it does not exist.
 ///
@@ -23,23 +48,114 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 ///     methanol_hplc(&test_user, &mut conn).expect("Failed to create methanol model");
 /// assert_eq!(methanol_model1, methanol_model2);
 /// ```
-pub fn methanol_hplc<C>(
-    user: &User,
+pub fn methanol_hplc<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<reagent_models::table>, TemplateError>
 where
-    TableBuilder<physical_asset_models::table>: Insert<C>,
+    C: Connection,
+    TableBuilder<reagent_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
     (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    ): LoadNestedFirst<physical_asset_models::table, C>,
+    ): LoadNestedFirst<reagent_models::table, C>,
 {
-    const METHANOL_NAME: &str = "Methanol, >= 99.8%, HPLC grade";
-    const METHANOL_DESCRIPTION: &str = "Methanol, >= 99.8%, HPLC grade";
-    reagent_model(user, METHANOL_NAME, METHANOL_DESCRIPTION, conn)
+    reagent_model(
+        context,
+        METHANOL_NAME,
+        METHANOL_DESCRIPTION,
+        &METHANOL_PROPERTIES,
+        conn,
+    )
+}
+
+/// Returns the methanol reagent model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`MethanolHplc`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_methanol_hplc(&mut conn).expect("Failed to look up the methanol reagent model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = methanol_hplc(&test_user, &mut conn).expect("Failed to create the methanol reagent model");
+/// assert_eq!(find_methanol_hplc(&mut conn).expect("Failed to look up the methanol reagent model"), Some(model));
+/// ```
+pub fn find_methanol_hplc<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    MethanolHplc::find(conn)
+}
+
+/// Returns the methanol reagent model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`MethanolHplc`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_methanol_hplc_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<reagent_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+{
+    MethanolHplc::find_in(namespace_name, conn)
+}
+
+/// Reference template of the methanol reagent model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MethanolHplc;
+
+impl AssetModelTemplate for MethanolHplc {
+    type Table = reagent_models::table;
+
+    const NAME: &'static str = METHANOL_NAME;
+    const DESCRIPTION: &'static str = METHANOL_HPLC.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        methanol_hplc(context, conn)
+    }
 }
@@ -1,57 +1,358 @@
 //! Submodule to initialize the `soils` in the database.
 
-use core_structures::{
-    SampleModel, SoilModel, User,
-    tables::insertables::{AssetModelSettable, SampleModelSettable},
-};
-use diesel::{OptionalExtension, PgConnection};
-use web_common_traits::database::{DispatchableInsertableVariant, Insertable};
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, Ontology, OntologyTerm, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
+use crate::prelude::reference_namespace;
+use crate::template::{AssetModelTemplate, SampleModelTemplate};
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_ownables::*;
+use aps::aps_sample_models::*;
+use aps::aps_sample_source_models::*;
+use aps::aps_users::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
 
-/// Returns the soil trackable.
+/// Name of the soil sample source model.
+pub const SOIL_NAME: &str = "Soil";
+
+/// Definition of the soil sample source model.
+pub const SOIL_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::SampleSourceModels,
+    SOIL_NAME,
+    "soils used in laboratory procedures",
+)
+.ontology(OntologyTerm::new(Ontology::Envo, "00001998"));
+
+/// Name of the soil sample model.
+pub const SOIL_SAMPLE_NAME: &str = "Soil Sample";
+
+/// Definition of the soil sample model.
+pub const SOIL_SAMPLE_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::SampleModels,
+    SOIL_SAMPLE_NAME,
+    "Samples used in laboratory procedures",
+)
+.sample_source(&SOIL_MODEL)
+.ontology(OntologyTerm::new(Ontology::Obi, "0000747"));
+
+/// Returns the soil sample source model, creating it if it does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the soil is being created.
-/// * `conn` - The database connection.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
+/// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
 ///
 /// * If the connection to the database fails.
-pub fn soil_model(user: &User, conn: &mut PgConnection) -> anyhow::Result<SoilModel> {
-    const SOIL: &str = "Soil";
+/// * If the insertion fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let soil_model1 = soil_model(&test_user, &mut conn).expect("Failed to create the soil model");
+/// let soil_model2 = soil_model(&test_user, &mut conn).expect("Failed to create the soil model");
+/// assert_eq!(soil_model1, soil_model2);
+/// ```
+pub fn soil_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<NestedModel<sample_source_models::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<sample_source_models::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SOIL_NAME, conn),
+        |conn| {
+            sample_source_models::table::builder()
+                .try_name(SOIL_NAME)
+                .map_err(TemplateError::attribute(SOIL_NAME, "name"))?
+                .try_description(SOIL_MODEL.description)
+                .map_err(TemplateError::attribute(SOIL_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SOIL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SOIL_NAME))
+        },
+        conn,
+    )
+}
 
-    if let Some(existing_soil) = SoilModel::from_name(SOIL, conn).optional()? {
-        return Ok(existing_soil);
-    }
+/// Returns the soil sample source model, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`SoilModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_soil_model(&mut conn).expect("Failed to look up the soil model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = soil_model(&test_user, &mut conn).expect("Failed to create the soil model");
+/// assert_eq!(find_soil_model(&mut conn).expect("Failed to look up the soil model"), Some(model));
+/// ```
+pub fn find_soil_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_source_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    SoilModel::find(conn)
+}
+
+/// Returns the soil sample source model from the provided namespace, if it
+/// exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`SoilModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_soil_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_source_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    SoilModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the soil sample source model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SoilModel;
+
+impl AssetModelTemplate for SoilModel {
+    type Table = sample_source_models::table;
 
-    Ok(SoilModel::new()
-        .name(SOIL)?
-        .description("soils used in laboratory procedures")?
-        .created_by(user)?
-        .insert(user.id, conn)?)
+    const NAME: &'static str = SOIL_NAME;
+    const DESCRIPTION: &'static str = SOIL_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        soil_model(context, conn)
+    }
 }
 
-/// Returns the sample trackable.
+/// Returns the soil sample model, creating it and its soil sample source
+/// model if they do not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user for whom the sample is being created.
-/// * `conn` - The database connection.
+/// * `context` - The namespace and users to create the models with, or simply
+///   the creating user.
+/// * `conn` - The database connection to use for the insertions.
 ///
 /// # Errors
 ///
 /// * If the connection to the database fails.
-pub fn soil_sample_model(user: &User, conn: &mut PgConnection) -> anyhow::Result<SampleModel> {
-    const SAMPLE: &str = "Soil Sample";
+/// * If any of the insertions fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let sample1 = soil_sample_model(&test_user, &mut conn).expect("Failed to create the soil sample model");
+/// let sample2 = soil_sample_model(&test_user, &mut conn).expect("Failed to create the soil sample model");
+/// assert_eq!(sample1, sample2);
+/// assert_eq!(sample1, SoilSampleModel::get_or_create(&test_user, &mut conn).expect("Failed to create the soil sample model"));
+/// assert!(find_soil_model(&mut conn).expect("Failed to look up the soil model").is_some());
+/// ```
+pub fn soil_sample_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<NestedModel<sample_models::table>, TemplateError>
+where
+    C: Connection,
+    TableBuilder<sample_models::table>: Insert<C>,
+    TableBuilder<sample_source_models::table>: Insert<C>,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+{
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SOIL_SAMPLE_NAME, conn),
+        |conn| {
+            let soil = soil_model(context, conn)?;
+            sample_models::table::builder()
+                .try_name(SOIL_SAMPLE_NAME)
+                .map_err(TemplateError::attribute(SOIL_SAMPLE_NAME, "name"))?
+                .try_description(SOIL_SAMPLE_MODEL.description)
+                .map_err(TemplateError::attribute(SOIL_SAMPLE_NAME, "description"))?
+                .try_sample_source_model_id(soil.get_column::<sample_source_models::id>())
+                .map_err(TemplateError::attribute(
+                    SOIL_SAMPLE_NAME,
+                    "sample_source_model_id",
+                ))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SOIL_SAMPLE_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SOIL_SAMPLE_NAME))
+        },
+        conn,
+    )
+}
 
-    if let Some(existing_sample) = SampleModel::from_name(SAMPLE, conn).optional()? {
-        return Ok(existing_sample);
-    }
+/// Returns the soil sample model, if it exists.
+///
+/// Shorthand for [`SampleModelTemplate::find`] on [`SoilSampleModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_soil_sample_model(&mut conn).expect("Failed to look up the soil sample model").is_none());
+/// let test_user = user(&mut conn);
+/// let model = soil_sample_model(&test_user, &mut conn).expect("Failed to create the soil sample model");
+/// assert_eq!(find_soil_sample_model(&mut conn).expect("Failed to look up the soil sample model"), Some(model));
+/// ```
+pub fn find_soil_sample_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+{
+    SoilSampleModel::find(conn)
+}
+
+/// Returns the soil sample model from the provided namespace, if it exists.
+///
+/// Shorthand for [`SampleModelTemplate::find_in`] on [`SoilSampleModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_soil_sample_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<sample_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+{
+    SoilSampleModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the soil sample model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SoilSampleModel;
 
-    Ok(SampleModel::new()
-        .name(SAMPLE)?
-        .description("Samples used in laboratory procedures")?
-        .sample_source_model(soil_model(user, conn)?)?
-        .created_by(user)?
-        .insert(user.id, conn)?)
+impl SampleModelTemplate for SoilSampleModel {
+    type Source = SoilModel;
+
+    const NAME: &'static str = SOIL_SAMPLE_NAME;
+    const DESCRIPTION: &'static str = SOIL_SAMPLE_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<sample_models::table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<sample_models::table>: Insert<C>,
+        TableBuilder<sample_source_models::table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_models::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_source_models::table, C>,
+    {
+        soil_sample_model(context, conn)
+    }
 }
@@ -1,3 +1,6 @@
 //! Submodule to initialize `tools` in the database.
 pub mod cutting_tools;
-pub use cutting_tools::{scalpel_model, scissor_model};
+pub use cutting_tools::{
+    SCALPEL_MODEL, SCISSOR_MODEL, ScalpelModel, ScissorModel, find_scalpel_model,
+    find_scalpel_model_in, find_scissor_model, find_scissor_model_in, scalpel_model, scissor_model,
+};
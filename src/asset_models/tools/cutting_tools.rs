@@ -1,20 +1,37 @@
 //! Submodule to initialize the `cutting_tools` in the database.
 
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::deprecation::load_active_reference_model;
+use crate::errors::TemplateError;
+use crate::lookup::get_or_insert;
 use crate::prelude::reference_namespace;
+use crate::template::AssetModelTemplate;
 use aps::aps_namespaced_ownables::*;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_physical_asset_models::*;
-use aps::aps_users::User;
 use aps::aps_users::*;
-use diesel_builders::{BuilderError, TableBuilder, prelude::*};
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Name of the physical asset model for a scalpel.
+pub const SCALPEL_NAME: &str = "Scalpel";
+
+/// Definition of the physical asset model for a scalpel.
+pub const SCALPEL_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PhysicalAssetModels,
+    SCALPEL_NAME,
+    "A scalpel used to cut samples.",
+);
 
 /// Returns the physical asset model for a scalpel, creating it if it does not
 /// exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user who is creating the physical asset model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -34,14 +51,12 @@ use diesel_builders::{BuilderError, TableBuilder, prelude::*};
 /// let scalpel_model2 = scalpel_model(&test_user, &mut conn).expect("Failed to create the scalpel model");
 /// assert_eq!(scalpel_model1, scalpel_model2);
 /// ```
-pub fn scalpel_model<C>(
-    user: &User,
+pub fn scalpel_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<physical_asset_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<physical_asset_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -50,40 +65,134 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<physical_asset_models::table, C>,
 {
-    const SCALPEL_NAME: &str = "Scalpel";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SCALPEL_NAME, conn),
+        |conn| {
+            physical_asset_models::table::builder()
+                .try_name(SCALPEL_NAME)
+                .map_err(TemplateError::attribute(SCALPEL_NAME, "name"))?
+                .try_description(SCALPEL_MODEL.description)
+                .map_err(TemplateError::attribute(SCALPEL_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SCALPEL_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SCALPEL_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the physical asset model for a scalpel, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`ScalpelModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_scalpel_model(&mut conn).expect("Failed to look up the physical asset model for a scalpel").is_none());
+/// let test_user = user(&mut conn);
+/// let model = scalpel_model(&test_user, &mut conn).expect("Failed to create the physical asset model for a scalpel");
+/// assert_eq!(find_scalpel_model(&mut conn).expect("Failed to look up the physical asset model for a scalpel"), Some(model));
+/// ```
+pub fn find_scalpel_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    ScalpelModel::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the physical asset model for a scalpel from the provided namespace,
+/// if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`ScalpelModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_scalpel_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    ScalpelModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the physical asset model for a scalpel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScalpelModel;
+
+impl AssetModelTemplate for ScalpelModel {
+    type Table = physical_asset_models::table;
+
+    const NAME: &'static str = SCALPEL_NAME;
+    const DESCRIPTION: &'static str = SCALPEL_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (SCALPEL_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        scalpel_model(context, conn)
     }
-
-    physical_asset_models::table::builder()
-        .try_name(SCALPEL_NAME)
-        .expect("Failed to set physical asset model name")
-        .try_description("A scalpel used to cut samples.")
-        .expect("Failed to set physical asset model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
 
+/// Name of the physical asset model for a pair of scissors.
+pub const SCISSORS_NAME: &str = "Scissors";
+
+/// Definition of the physical asset model for a pair of scissors.
+pub const SCISSOR_MODEL: TemplateDefinition = TemplateDefinition::new(
+    ModelTable::PhysicalAssetModels,
+    SCISSORS_NAME,
+    "A pair of scissors.",
+);
+
 /// Returns the physical asset model for a pair of scissors, creating it if it
 /// does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user who is creating the physical asset model.
+/// * `context` - The namespace and users to create the model with, or simply
+///   the creating user.
 /// * `conn` - The database connection to use for the insertion.
 ///
 /// # Errors
@@ -103,14 +212,12 @@ where
 /// let scissor_model2 = scissor_model(&test_user, &mut conn).expect("Failed to create the scissor model");
 /// assert_eq!(scissor_model1, scissor_model2);
 /// ```
-pub fn scissor_model<C>(
-    user: &User,
+pub fn scissor_model<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<
-    NestedModel<physical_asset_models::table>,
-    BuilderError<validation_errors::ValidationError>,
->
+) -> Result<NestedModel<physical_asset_models::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<physical_asset_models::table>: Insert<C>,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
@@ -119,30 +226,113 @@ where
         (namespaced_ownables::name,),
     ): LoadNestedFirst<physical_asset_models::table, C>,
 {
-    const SCISSORS_NAME: &str = "Scissors";
+    let context = context.into();
+    let reference_namespace = reference_namespace(context, conn)?;
+    get_or_insert(
+        |conn| load_active_reference_model(&context, &reference_namespace, SCISSORS_NAME, conn),
+        |conn| {
+            physical_asset_models::table::builder()
+                .try_name(SCISSORS_NAME)
+                .map_err(TemplateError::attribute(SCISSORS_NAME, "name"))?
+                .try_description(SCISSOR_MODEL.description)
+                .map_err(TemplateError::attribute(SCISSORS_NAME, "description"))?
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .owner_id(context.owner.get_column::<users::id>())
+                .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                .template_id(&context, SCISSORS_NAME)
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(SCISSORS_NAME))
+        },
+        conn,
+    )
+}
+
+/// Returns the physical asset model for a pair of scissors, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find`] on [`ScissorModel`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// assert!(find_scissor_model(&mut conn).expect("Failed to look up the physical asset model for a pair of scissors").is_none());
+/// let test_user = user(&mut conn);
+/// let model = scissor_model(&test_user, &mut conn).expect("Failed to create the physical asset model for a pair of scissors");
+/// assert_eq!(find_scissor_model(&mut conn).expect("Failed to look up the physical asset model for a pair of scissors"), Some(model));
+/// ```
+pub fn find_scissor_model<C>(
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    ScissorModel::find(conn)
+}
 
-    let reference_namespace = reference_namespace(user, conn)?;
-    if let Ok(existing) = <(
+/// Returns the physical asset model for a pair of scissors from the provided
+/// namespace, if it exists.
+///
+/// Shorthand for [`AssetModelTemplate::find_in`] on [`ScissorModel`].
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn find_scissor_model_in<C>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<physical_asset_models::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
         namespaced_ownables::namespace_id,
         (namespaced_ownables::name,),
-    )>::load_nested_first(
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+{
+    ScissorModel::find_in(namespace_name, conn)
+}
+
+/// Reference template of the physical asset model for a pair of scissors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScissorModel;
+
+impl AssetModelTemplate for ScissorModel {
+    type Table = physical_asset_models::table;
+
+    const NAME: &'static str = SCISSORS_NAME;
+    const DESCRIPTION: &'static str = SCISSOR_MODEL.description;
+
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
         (
-            reference_namespace.get_column::<namespaces::id>(),
-            (SCISSORS_NAME,),
-        ),
-        conn,
-    ) {
-        return Ok(existing);
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        scissor_model(context, conn)
     }
-
-    physical_asset_models::table::builder()
-        .try_name(SCISSORS_NAME)
-        .expect("Failed to set physical asset model name")
-        .try_description("A pair of scissors.")
-        .expect("Failed to set physical asset model description")
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .owner_id(user.get_column::<users::id>())
-        .namespace_id(reference_namespace.get_column::<namespaces::id>())
-        .insert_nested(conn)
 }
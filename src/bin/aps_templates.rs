@@ -0,0 +1,235 @@
+//! Command-line interface seeding, planning, checking and exporting the
+//! reference catalog of a database.
+//!
+//! Built only with the `cli` feature:
+//!
+//! ```sh
+//! cargo run --features cli -- --database-url postgres://localhost/aps --user alice seed
+//! ```
+
+use std::process::ExitCode;
+
+use aps::aps_users::*;
+use aps_templates::prelude::*;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use diesel::{Connection, PgConnection};
+use diesel_builders::prelude::*;
+
+/// Seeds and inspects the APS reference catalog.
+#[derive(Debug, Parser)]
+#[command(name = "aps-templates", version, about)]
+struct Cli {
+    /// URL of the database holding the reference catalog.
+    #[arg(long, env = "DATABASE_URL", global = true)]
+    database_url: Option<String>,
+    /// Username of the user acting on the reference catalog.
+    #[arg(long, env = "APS_TEMPLATES_USER", global = true)]
+    user: Option<String>,
+    /// Name of the namespace holding the reference catalog.
+    #[arg(long, default_value = REFERENCE_NAMESPACE_NAME, global = true)]
+    namespace: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Subcommands of the command-line interface.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Applies the pending template migrations, then creates the missing
+    /// reference models and compatibility rules.
+    Seed {
+        /// Creates the models with deterministic UUIDv5 identifiers.
+        #[arg(long)]
+        deterministic_ids: bool,
+    },
+    /// Applies the pending template migrations, which seeding also does.
+    Migrate,
+    /// Prints which reference models seeding would reuse and create.
+    Plan,
+    /// Prints the reference models differing from their definitions, exiting
+    /// with a failure if any does.
+    CheckDrift,
+    /// Prints the stored and current catalog versions, exiting with a failure
+    /// if the stored catalog needs a reconcile.
+    CheckVersion,
+    /// Prints the reference catalog in the provided format.
+    Export(ExportArgs),
+    /// Prints the reference templates with their tables.
+    List,
+}
+
+/// Arguments of the `export` subcommand.
+#[derive(Debug, Args)]
+struct ExportArgs {
+    /// Format of the export.
+    #[arg(long, value_enum, default_value_t = ExportFormat::Json)]
+    format: ExportFormat,
+    /// Exports the JSON without reading the identifiers from the database.
+    #[arg(long)]
+    offline: bool,
+    /// Creates the models of the SQL script with deterministic UUIDv5
+    /// identifiers.
+    #[arg(long)]
+    deterministic_ids: bool,
+}
+
+/// Formats the reference catalog can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// JSON, with the database identifiers unless `--offline` is set.
+    Json,
+    /// JSON-LD, with the stable IRIs of the templates.
+    JsonLd,
+    /// RDF Turtle, with the stable IRIs of the templates.
+    Turtle,
+    /// Graphviz DOT graph of the compatibility rules.
+    Dot,
+    /// Mermaid flowchart of the compatibility rules.
+    Mermaid,
+    /// Idempotent SQL seed script, owned by the acting user.
+    Sql,
+}
+
+/// Error of the command-line interface.
+#[derive(Debug, thiserror::Error)]
+enum CliError {
+    /// An option needed by the subcommand is missing.
+    #[error("The `--{0}` option is required by this subcommand")]
+    Missing(&'static str),
+    /// The connection to the database failed.
+    #[error("Failed to connect to the database: {0}")]
+    Connection(#[from] diesel::ConnectionError),
+    /// The acting user could not be loaded.
+    #[error("Failed to load the user `{0}`: {1}")]
+    User(String, diesel::result::Error),
+    /// A template operation failed.
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+}
+
+impl Cli {
+    /// Returns a connection to the database.
+    fn connection(&self) -> Result<PgConnection, CliError> {
+        let url = self
+            .database_url
+            .as_deref()
+            .ok_or(CliError::Missing("database-url"))?;
+        Ok(PgConnection::establish(url)?)
+    }
+
+    /// Returns the acting user.
+    fn user(&self, conn: &mut PgConnection) -> Result<User, CliError> {
+        let username = self.user.as_deref().ok_or(CliError::Missing("user"))?;
+        <(users::username,)>::load_nested_first((username,), conn)
+            .map_err(|error| CliError::User(username.to_owned(), error))
+    }
+
+    /// Runs the subcommand, returning whether it succeeded.
+    fn run(&self) -> Result<bool, CliError> {
+        match &self.command {
+            Command::Seed { deterministic_ids } => {
+                let mut conn = self.connection()?;
+                let user = self.user(&mut conn)?;
+                let context = TemplateContext::new(&user)
+                    .namespace_name(&self.namespace)
+                    .deterministic_ids(*deterministic_ids);
+                let (migrations, plan, rules) = conn.transaction(|conn| {
+                    let migrations = apply_template_migrations_in(&self.namespace, conn)?;
+                    let plan = plan_reference_catalog_in(&self.namespace, conn)?;
+                    let rules = init_compatibility_rules(context, conn)?;
+                    Ok::<_, TemplateError>((migrations, plan, rules))
+                })?;
+                for migration in migrations {
+                    println!("applied `{migration}` to `{}`", self.namespace);
+                }
+                for planned in plan.to_create() {
+                    println!("planned `{}` in `{}`", planned.template, planned.table);
+                }
+                let planned = plan.to_create().count();
+                println!(
+                    "seeded `{}`: {planned} templates planned for creation and {} for reuse, {rules} compatibility rules created",
+                    self.namespace,
+                    plan.templates.len() - planned,
+                );
+            }
+            Command::Migrate => {
+                let mut conn = self.connection()?;
+                for migration in apply_template_migrations_in(&self.namespace, &mut conn)? {
+                    println!("applied `{migration}` to `{}`", self.namespace);
+                }
+            }
+            Command::Plan => {
+                let mut conn = self.connection()?;
+                print!("{}", plan_reference_catalog_in(&self.namespace, &mut conn)?);
+            }
+            Command::CheckDrift => {
+                let mut conn = self.connection()?;
+                let drifts = check_drift_in(&self.namespace, &mut conn)?;
+                for drift in &drifts {
+                    match drift {
+                        TemplateDrift::Missing { template } => println!("missing `{template}`"),
+                        TemplateDrift::Changed { template, fields } => {
+                            println!("changed `{template}`");
+                            for field in fields {
+                                println!(
+                                    "    {}: expected `{}`, stored `{}`",
+                                    field.field, field.expected, field.stored
+                                );
+                            }
+                        }
+                    }
+                }
+                return Ok(drifts.is_empty());
+            }
+            Command::CheckVersion => {
+                let mut conn = self.connection()?;
+                let stored = stored_catalog_version_in(&self.namespace, &mut conn)?;
+                println!("current: {}", CatalogVersion::current());
+                println!("stored: {}", stored.as_deref().unwrap_or("-"));
+                let status = catalog_status_in(&self.namespace, &mut conn)?;
+                return Ok(!status.needs_reconcile());
+            }
+            Command::Export(args) => {
+                let export = match args.format {
+                    ExportFormat::Json if args.offline => export_catalog_json_offline()?,
+                    ExportFormat::Json => {
+                        let mut conn = self.connection()?;
+                        let export = CatalogExport::online_in(&self.namespace, &mut conn)?;
+                        serde_json::to_string_pretty(&export).map_err(TemplateError::from)?
+                    }
+                    ExportFormat::JsonLd => export_catalog_json_ld(),
+                    ExportFormat::Turtle => export_catalog_turtle(),
+                    ExportFormat::Dot => export_compatibility_dot(),
+                    ExportFormat::Mermaid => export_compatibility_mermaid(),
+                    ExportFormat::Sql => {
+                        let mut conn = self.connection()?;
+                        let user = self.user(&mut conn)?;
+                        export_seed_sql(
+                            TemplateContext::new(&user)
+                                .namespace_name(&self.namespace)
+                                .deterministic_ids(args.deterministic_ids),
+                        )
+                    }
+                };
+                println!("{export}");
+            }
+            Command::List => {
+                for definition in REFERENCE_TEMPLATES {
+                    println!("{}\t{}", definition.table.table_name(), definition.name);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Submodule seeding the whole reference catalog at once.
+
+use crate::deprecation::record_template_deprecations_in;
+use crate::migrations::apply_template_migrations_in;
+use crate::prelude::*;
+use crate::version::record_catalog_version_in;
+use aps::aps_ball_mill_machine_models::*;
+use aps::aps_bead_models::*;
+use aps::aps_centrifuge_models::*;
+use aps::aps_container_models::*;
+use aps::aps_container_sealer_models::*;
+use aps::aps_digital_asset_models::*;
+use aps::aps_freeze_dryer_models::*;
+use aps::aps_freezer_models::*;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_packaging_models::*;
+use aps::aps_personal_protective_equipment_models::*;
+use aps::aps_phone_device_models::*;
+use aps::aps_physical_asset_models::*;
+use aps::aps_pipette_tip_models::*;
+use aps::aps_reagent_models::*;
+use aps::aps_sample_models::*;
+use aps::aps_sample_source_models::*;
+use aps::aps_volume_measuring_device_models::*;
+use aps::aps_volumetric_container_models::*;
+use aps::aps_weighing_device_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// Handle on every reference model defined in [`asset_models`](crate::asset_models).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceCatalog {
+    /// The reference namespace owning all of the models below.
+    pub reference_namespace: NestedModel<namespaces::table>,
+    /// The 3mm metal bead model.
+    pub bead_3mm: NestedModel<bead_models::table>,
+    /// The 1L bottle model.
+    pub bottle_1l: NestedModel<volumetric_container_models::table>,
+    /// The polystyrene box model.
+    pub polystyrene_box: NestedModel<container_models::table>,
+    /// The vial rack 1.5ml model.
+    pub vial_rack_1_5ml: NestedModel<container_models::table>,
+    /// The conical centrifugal tube 50ml model.
+    pub conical_centrifugal_tube_50ml: NestedModel<volumetric_container_models::table>,
+    /// The rack model for conical centrifugal tubes of 50ml.
+    pub conical_centrifugal_tube_50ml_rack: NestedModel<container_models::table>,
+    /// The safelock tube 2ml model.
+    pub safelock_tubes_2ml: NestedModel<volumetric_container_models::table>,
+    /// The sealed cap for vial 1.5ml model.
+    pub sealed_cap_vial_1_5ml: NestedModel<container_sealer_models::table>,
+    /// The split cap for vial 1.5ml model.
+    pub split_cap_vial_1_5ml: NestedModel<container_sealer_models::table>,
+    /// The vial insert 200μl model.
+    pub vial_insert_200ul: NestedModel<volumetric_container_models::table>,
+    /// The vial 1.5ml model.
+    pub vial_1_5ml: NestedModel<volumetric_container_models::table>,
+    /// The coffee filter wrapper model.
+    pub coffee_filter_wrapper: NestedModel<packaging_models::table>,
+    /// The ball mill machine model.
+    pub ball_mill_machine: NestedModel<ball_mill_machine_models::table>,
+    /// The safelock centrifuge model.
+    pub safelock_centrifuge: NestedModel<centrifuge_models::table>,
+    /// The freeze dryer model.
+    pub freeze_dryer: NestedModel<freeze_dryer_models::table>,
+    /// The -80°C freezer model.
+    pub freezer: NestedModel<freezer_models::table>,
+    /// The phone model.
+    pub phone_model: NestedModel<phone_device_models::table>,
+    /// The 200μl pipette tip model.
+    pub pipette_tip_200ul: NestedModel<pipette_tip_models::table>,
+    /// The 1ml pipette tip model.
+    pub pipette_tip_1000ul: NestedModel<pipette_tip_models::table>,
+    /// The 200μl pipette model.
+    pub pipette_200ul: NestedModel<volume_measuring_device_models::table>,
+    /// The 1000µl pipette model.
+    pub pipette_1000ul: NestedModel<volume_measuring_device_models::table>,
+    /// The generic volume measuring device model.
+    pub volume_measuring_device_model: NestedModel<volume_measuring_device_models::table>,
+    /// The weighing scale model.
+    pub weighing_scale: NestedModel<weighing_device_models::table>,
+    /// The marker arrow model.
+    pub marker_arrow_model: NestedModel<physical_asset_models::table>,
+    /// The photograph model.
+    pub photograph_model: NestedModel<digital_asset_models::table>,
+    /// The panel model.
+    pub panel_model: NestedModel<physical_asset_models::table>,
+    /// The latex gloves model.
+    pub glove_model: NestedModel<personal_protective_equipment_models::table>,
+    /// The distilled water reagent model.
+    pub distilled_water: NestedModel<reagent_models::table>,
+    /// The absolute ethanol reagent model.
+    pub absolute_ethanol: NestedModel<reagent_models::table>,
+    /// The formic acid reagent model.
+    pub formic_acid: NestedModel<reagent_models::table>,
+    /// The liquid nitrogen reagent model.
+    pub liquid_nitrogen: NestedModel<reagent_models::table>,
+    /// The HPLC grade methanol reagent model.
+    pub methanol_hplc: NestedModel<reagent_models::table>,
+    /// The scalpel model.
+    pub scalpel_model: NestedModel<physical_asset_models::table>,
+    /// The scissors model.
+    pub scissor_model: NestedModel<physical_asset_models::table>,
+    /// The organism sample source model.
+    pub organism_model: NestedModel<sample_source_models::table>,
+    /// The organism sample model.
+    pub organism_sample_model: NestedModel<sample_models::table>,
+    /// The soil sample source model.
+    pub soil_model: NestedModel<sample_source_models::table>,
+    /// The soil sample model.
+    pub soil_sample_model: NestedModel<sample_models::table>,
+}
+
+/// Returns the whole reference catalog, creating within a single transaction
+/// any of its models that do not exist yet.
+///
+/// The pending [`TEMPLATE_MIGRATIONS`] are applied first, so that renamed
+/// templates reuse their former models instead of creating new ones. The
+/// deprecated models are part of the catalog too, so the
+/// [`DeprecationPolicy`] of the context is ignored, and the deprecations
+/// declared by the templates are then written to the namespace.
+///
+/// If no model of the namespace differs from its definition afterwards, the
+/// [`CatalogVersion`] of the running code is recorded in the namespace, see
+/// [`catalog_status`].
+///
+/// # Arguments
+///
+/// * `context` - The namespace and users to create the missing models with, or
+///   simply the creating user.
+/// * `conn` - The database connection to use for the insertions.
+///
+/// # Errors
+///
+/// * If the connection to the database fails.
+/// * If any of the insertions fails, in which case none of them is committed.
+/// * If any of the pending migrations cannot be applied.
+/// * If the catalog version cannot be recorded.
+///
+/// # Example
+///
+/// ```rust
+/// use aps::aps_reagent_models::*;
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel_builders::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let catalog1 = seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// let catalog2 = seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// assert_eq!(catalog1, catalog2);
+/// assert_eq!(catalog1.vial_1_5ml, vial_1_5ml(&test_user, &mut conn).expect("Failed to create vial model"));
+///
+/// // Every lab namespace gets its own reagent models, with the same CAS codes.
+/// let lab = TemplateContext::new(&test_user).namespace_name("lab_reference");
+/// let lab_catalog = seed_reference_catalog(lab, &mut conn).expect("Failed to seed the lab catalog");
+/// assert_ne!(lab_catalog.methanol_hplc, catalog1.methanol_hplc);
+/// assert_eq!(
+///     lab_catalog.methanol_hplc.get_column::<reagent_models::cas_code>(),
+///     catalog1.methanol_hplc.get_column::<reagent_models::cas_code>()
+/// );
+/// ```
+pub fn seed_reference_catalog<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<ReferenceCatalog, TemplateError>
+where
+    C: MigrationWriter,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    TableBuilder<ball_mill_machine_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<ball_mill_machine_models::table, C>,
+    TableBuilder<bead_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<bead_models::table, C>,
+    TableBuilder<centrifuge_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<centrifuge_models::table, C>,
+    TableBuilder<container_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_models::table, C>,
+    TableBuilder<container_sealer_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+    TableBuilder<digital_asset_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<digital_asset_models::table, C>,
+    TableBuilder<freeze_dryer_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freeze_dryer_models::table, C>,
+    TableBuilder<freezer_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freezer_models::table, C>,
+    TableBuilder<packaging_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<packaging_models::table, C>,
+    TableBuilder<personal_protective_equipment_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<personal_protective_equipment_models::table, C>,
+    TableBuilder<phone_device_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<phone_device_models::table, C>,
+    TableBuilder<physical_asset_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+    TableBuilder<pipette_tip_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+    TableBuilder<reagent_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+    TableBuilder<sample_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+    TableBuilder<sample_source_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+    TableBuilder<volume_measuring_device_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+    TableBuilder<volumetric_container_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+    TableBuilder<weighing_device_models::table>: Insert<C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<weighing_device_models::table, C>,
+{
+    let context = context.into().deprecation_policy(DeprecationPolicy::Allow);
+    conn.transaction(|conn| {
+        apply_template_migrations_in(context.namespace_name, conn)?;
+        let catalog = ReferenceCatalog {
+            reference_namespace: reference_namespace(context, conn)?,
+            bead_3mm: bead_3mm(context, conn)?,
+            bottle_1l: bottle_1l(context, conn)?,
+            polystyrene_box: polystyrene_box(context, conn)?,
+            vial_rack_1_5ml: vial_rack_1_5ml(context, conn)?,
+            conical_centrifugal_tube_50ml: conical_centrifugal_tube_50ml(context, conn)?,
+            conical_centrifugal_tube_50ml_rack: conical_centrifugal_tube_50ml_rack(context, conn)?,
+            safelock_tubes_2ml: safelock_tubes_2ml(context, conn)?,
+            sealed_cap_vial_1_5ml: sealed_cap_vial_1_5ml(context, conn)?,
+            split_cap_vial_1_5ml: split_cap_vial_1_5ml(context, conn)?,
+            vial_insert_200ul: vial_insert_200ul(context, conn)?,
+            vial_1_5ml: vial_1_5ml(context, conn)?,
+            coffee_filter_wrapper: coffee_filter_wrapper(context, conn)?,
+            ball_mill_machine: ball_mill_machine(context, conn)?,
+            safelock_centrifuge: safelock_centrifuge(context, conn)?,
+            freeze_dryer: freeze_dryer(context, conn)?,
+            freezer: freezer(context, conn)?,
+            phone_model: phone_model(context, conn)?,
+            pipette_tip_200ul: pipette_tip_200ul(context, conn)?,
+            pipette_tip_1000ul: pipette_tip_1000ul(context, conn)?,
+            pipette_200ul: pipette_200ul(context, conn)?,
+            pipette_1000ul: pipette_1000ul(context, conn)?,
+            volume_measuring_device_model: volume_measuring_device_model(context, conn)?,
+            weighing_scale: weighing_scale(context, conn)?,
+            marker_arrow_model: marker_arrow_model(context, conn)?,
+            photograph_model: photograph_model(context, conn)?,
+            panel_model: panel_model(context, conn)?,
+            glove_model: glove_model(context, conn)?,
+            distilled_water: distilled_water(context, conn)?,
+            absolute_ethanol: absolute_ethanol(context, conn)?,
+            formic_acid: formic_acid(context, conn)?,
+            liquid_nitrogen: liquid_nitrogen(context, conn)?,
+            methanol_hplc: methanol_hplc(context, conn)?,
+            scalpel_model: scalpel_model(context, conn)?,
+            scissor_model: scissor_model(context, conn)?,
+            organism_model: organism_model(context, conn)?,
+            organism_sample_model: organism_sample_model(context, conn)?,
+            soil_model: soil_model(context, conn)?,
+            soil_sample_model: soil_sample_model(context, conn)?,
+        };
+        record_template_deprecations_in(context.namespace_name, conn)?;
+        record_catalog_version_in(context.namespace_name, conn)?;
+        Ok(catalog)
+    })
+}
@@ -0,0 +1,172 @@
+//! Submodule defining the context in which the templates are created.
+
+use crate::deprecation::DeprecationPolicy;
+use crate::reference_namespaces::{REFERENCE_NAMESPACE_NAME, namespace_uuid, template_uuid};
+use aps::aps_ownables::*;
+use aps::aps_users::User;
+use diesel_builders::{TableBuilder, prelude::*};
+use rosetta_uuid::Uuid;
+
+/// Namespace and users with which the reference models are created.
+///
+/// A plain `&User` converts into the default context, which creates the models
+/// in the [`REFERENCE_NAMESPACE_NAME`] namespace and uses that user as owner,
+/// creator and editor.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let context = TemplateContext::new(&test_user).namespace_name("lab_reference");
+/// let lab_vial = vial_1_5ml(context, &mut conn).expect("Failed to create vial model");
+/// let reference_vial = vial_1_5ml(&test_user, &mut conn).expect("Failed to create vial model");
+/// assert_ne!(lab_vial, reference_vial);
+/// ```
+///
+/// With deterministic identifiers, the models are inserted with UUIDv5
+/// identifiers derived from the namespace and template names, so that every
+/// deployment stores the same reference model under the same identifier:
+///
+/// ```rust
+/// use aps::aps_volumetric_container_models::*;
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel_builders::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let context = TemplateContext::new(&test_user).deterministic_ids(true);
+/// let vial = vial_1_5ml(context, &mut conn).expect("Failed to create vial model");
+/// assert_eq!(
+///     vial.get_column::<volumetric_container_models::id>().to_owned(),
+///     template_uuid(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name)
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateContext<'a> {
+    /// Name of the namespace in which the models are looked up and created.
+    pub namespace_name: &'a str,
+    /// The user owning the created models.
+    pub owner: &'a User,
+    /// The user recorded as creator of the created models.
+    pub creator: &'a User,
+    /// The user recorded as editor of the created models.
+    pub editor: &'a User,
+    /// Whether the namespace and the models are created with deterministic
+    /// identifiers, see [`template_uuid`].
+    pub deterministic_ids: bool,
+    /// How the lookups behave when the model they look up is deprecated.
+    pub deprecation_policy: DeprecationPolicy,
+}
+
+impl<'a> TemplateContext<'a> {
+    /// Returns the default context, in which the provided user owns, creates
+    /// and edits the models of the reference namespace.
+    #[must_use]
+    pub fn new(user: &'a User) -> Self {
+        Self {
+            namespace_name: REFERENCE_NAMESPACE_NAME,
+            owner: user,
+            creator: user,
+            editor: user,
+            deterministic_ids: false,
+            deprecation_policy: DeprecationPolicy::default(),
+        }
+    }
+
+    /// Sets the namespace in which the models are looked up and created.
+    #[must_use]
+    pub fn namespace_name(mut self, namespace_name: &'a str) -> Self {
+        self.namespace_name = namespace_name;
+        self
+    }
+
+    /// Sets the user owning the created models.
+    #[must_use]
+    pub fn owner(mut self, owner: &'a User) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Sets the user recorded as creator of the created models.
+    #[must_use]
+    pub fn creator(mut self, creator: &'a User) -> Self {
+        self.creator = creator;
+        self
+    }
+
+    /// Sets the user recorded as editor of the created models.
+    #[must_use]
+    pub fn editor(mut self, editor: &'a User) -> Self {
+        self.editor = editor;
+        self
+    }
+
+    /// Sets whether the namespace and the models are created with
+    /// deterministic identifiers, see [`template_uuid`].
+    #[must_use]
+    pub fn deterministic_ids(mut self, deterministic_ids: bool) -> Self {
+        self.deterministic_ids = deterministic_ids;
+        self
+    }
+
+    /// Sets how the lookups behave when the model they look up is deprecated.
+    #[must_use]
+    pub fn deprecation_policy(mut self, deprecation_policy: DeprecationPolicy) -> Self {
+        self.deprecation_policy = deprecation_policy;
+        self
+    }
+
+    /// Returns the identifier the namespace is created with, if the context
+    /// uses deterministic identifiers.
+    #[must_use]
+    pub fn namespace_id(&self) -> Option<Uuid> {
+        self.deterministic_ids
+            .then(|| namespace_uuid(self.namespace_name))
+    }
+
+    /// Returns the identifier the model of the template with the provided
+    /// name is created with, if the context uses deterministic identifiers.
+    #[must_use]
+    pub fn template_id(&self, template_name: &str) -> Option<Uuid> {
+        self.deterministic_ids
+            .then(|| template_uuid(self.namespace_name, template_name))
+    }
+}
+
+/// Extension of the table builders setting the identifier of the inserted
+/// row when the context uses deterministic identifiers.
+#[doc(hidden)]
+pub trait WithContextId: Sized {
+    /// Sets the identifier of the row, if any.
+    fn with_id(self, id: Option<Uuid>) -> Self;
+
+    /// Sets the identifier of the model of the template with the provided
+    /// name, if the context uses deterministic identifiers.
+    fn template_id(self, context: &TemplateContext<'_>, template_name: &str) -> Self {
+        self.with_id(context.template_id(template_name))
+    }
+}
+
+impl<T> WithContextId for TableBuilder<T>
+where
+    T: diesel::Table,
+    TableBuilder<T>: SetColumn<ownables::id>,
+{
+    fn with_id(self, id: Option<Uuid>) -> Self {
+        match id {
+            Some(id) => self.set_column::<ownables::id>(id),
+            None => self,
+        }
+    }
+}
+
+impl<'a> From<&'a User> for TemplateContext<'a> {
+    fn from(user: &'a User) -> Self {
+        Self::new(user)
+    }
+}
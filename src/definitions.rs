@@ -0,0 +1,327 @@
+//! Submodule describing the reference templates as plain data.
+//!
+//! The get-or-create functions in [`asset_models`](crate::asset_models) build
+//! their models from these definitions, so that tooling which needs to know
+//! what a template looks like (e.g. the drift detection) never disagrees with
+//! what gets inserted.
+
+/// Table in which the model of a template is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ModelTable {
+    /// The `ball_mill_machine_models` table.
+    BallMillMachineModels,
+    /// The `bead_models` table.
+    BeadModels,
+    /// The `centrifuge_models` table.
+    CentrifugeModels,
+    /// The `container_models` table.
+    ContainerModels,
+    /// The `container_sealer_models` table.
+    ContainerSealerModels,
+    /// The `digital_asset_models` table.
+    DigitalAssetModels,
+    /// The `freeze_dryer_models` table.
+    FreezeDryerModels,
+    /// The `freezer_models` table.
+    FreezerModels,
+    /// The `packaging_models` table.
+    PackagingModels,
+    /// The `personal_protective_equipment_models` table.
+    PersonalProtectiveEquipmentModels,
+    /// The `phone_device_models` table.
+    PhoneDeviceModels,
+    /// The `physical_asset_models` table.
+    PhysicalAssetModels,
+    /// The `pipette_tip_models` table.
+    PipetteTipModels,
+    /// The `reagent_models` table.
+    ReagentModels,
+    /// The `sample_models` table.
+    SampleModels,
+    /// The `sample_source_models` table.
+    SampleSourceModels,
+    /// The `volume_measuring_device_models` table.
+    VolumeMeasuringDeviceModels,
+    /// The `volumetric_container_models` table.
+    VolumetricContainerModels,
+    /// The `weighing_device_models` table.
+    WeighingDeviceModels,
+}
+
+impl ModelTable {
+    /// Returns the name of the table.
+    #[must_use]
+    pub const fn table_name(self) -> &'static str {
+        match self {
+            Self::BallMillMachineModels => "ball_mill_machine_models",
+            Self::BeadModels => "bead_models",
+            Self::CentrifugeModels => "centrifuge_models",
+            Self::ContainerModels => "container_models",
+            Self::ContainerSealerModels => "container_sealer_models",
+            Self::DigitalAssetModels => "digital_asset_models",
+            Self::FreezeDryerModels => "freeze_dryer_models",
+            Self::FreezerModels => "freezer_models",
+            Self::PackagingModels => "packaging_models",
+            Self::PersonalProtectiveEquipmentModels => "personal_protective_equipment_models",
+            Self::PhoneDeviceModels => "phone_device_models",
+            Self::PhysicalAssetModels => "physical_asset_models",
+            Self::PipetteTipModels => "pipette_tip_models",
+            Self::ReagentModels => "reagent_models",
+            Self::SampleModels => "sample_models",
+            Self::SampleSourceModels => "sample_source_models",
+            Self::VolumeMeasuringDeviceModels => "volume_measuring_device_models",
+            Self::VolumetricContainerModels => "volumetric_container_models",
+            Self::WeighingDeviceModels => "weighing_device_models",
+        }
+    }
+
+    /// Returns the model table the table extends, or `None` for the tables
+    /// directly extending `asset_models`.
+    #[must_use]
+    pub const fn extended_table(self) -> Option<Self> {
+        match self {
+            Self::DigitalAssetModels | Self::PhysicalAssetModels => None,
+            Self::VolumetricContainerModels => Some(Self::ContainerModels),
+            Self::BallMillMachineModels
+            | Self::BeadModels
+            | Self::CentrifugeModels
+            | Self::ContainerModels
+            | Self::ContainerSealerModels
+            | Self::FreezeDryerModels
+            | Self::FreezerModels
+            | Self::PackagingModels
+            | Self::PersonalProtectiveEquipmentModels
+            | Self::PhoneDeviceModels
+            | Self::PipetteTipModels
+            | Self::ReagentModels
+            | Self::SampleModels
+            | Self::SampleSourceModels
+            | Self::VolumeMeasuringDeviceModels
+            | Self::WeighingDeviceModels => Some(Self::PhysicalAssetModels),
+        }
+    }
+
+    /// Returns the name of the RDF class of the models stored in the table,
+    /// e.g. `VolumetricContainerModel`.
+    #[must_use]
+    pub const fn class_name(self) -> &'static str {
+        match self {
+            Self::BallMillMachineModels => "BallMillMachineModel",
+            Self::BeadModels => "BeadModel",
+            Self::CentrifugeModels => "CentrifugeModel",
+            Self::ContainerModels => "ContainerModel",
+            Self::ContainerSealerModels => "ContainerSealerModel",
+            Self::DigitalAssetModels => "DigitalAssetModel",
+            Self::FreezeDryerModels => "FreezeDryerModel",
+            Self::FreezerModels => "FreezerModel",
+            Self::PackagingModels => "PackagingModel",
+            Self::PersonalProtectiveEquipmentModels => "PersonalProtectiveEquipmentModel",
+            Self::PhoneDeviceModels => "PhoneDeviceModel",
+            Self::PhysicalAssetModels => "PhysicalAssetModel",
+            Self::PipetteTipModels => "PipetteTipModel",
+            Self::ReagentModels => "ReagentModel",
+            Self::SampleModels => "SampleModel",
+            Self::SampleSourceModels => "SampleSourceModel",
+            Self::VolumeMeasuringDeviceModels => "VolumeMeasuringDeviceModel",
+            Self::VolumetricContainerModels => "VolumetricContainerModel",
+            Self::WeighingDeviceModels => "WeighingDeviceModel",
+        }
+    }
+}
+
+/// Ontology providing terms the reference models can be mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Ontology {
+    /// The Chemical Entities of Biological Interest ontology.
+    Chebi,
+    /// The Environment Ontology.
+    Envo,
+    /// The Ontology for Biomedical Investigations.
+    Obi,
+}
+
+impl Ontology {
+    /// Returns the prefix of the terms of the ontology, e.g. `CHEBI`.
+    #[must_use]
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Self::Chebi => "CHEBI",
+            Self::Envo => "ENVO",
+            Self::Obi => "OBI",
+        }
+    }
+}
+
+/// Term of an ontology a reference model is mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OntologyTerm {
+    /// Ontology defining the term.
+    pub ontology: Ontology,
+    /// Local identifier of the term, e.g. `17790` for methanol in CHEBI.
+    pub id: &'static str,
+}
+
+impl OntologyTerm {
+    /// Base IRI of the OBO Foundry ontologies.
+    pub const OBO_IRI: &'static str = "http://purl.obolibrary.org/obo/";
+
+    /// Returns a new term of the provided ontology.
+    #[must_use]
+    pub const fn new(ontology: Ontology, id: &'static str) -> Self {
+        Self { ontology, id }
+    }
+
+    /// Returns the compact identifier of the term, e.g. `CHEBI:17790`.
+    #[must_use]
+    pub fn curie(&self) -> String {
+        format!("{}:{}", self.ontology.prefix(), self.id)
+    }
+
+    /// Returns the IRI of the term, e.g.
+    /// `http://purl.obolibrary.org/obo/CHEBI_17790`.
+    #[must_use]
+    pub fn iri(&self) -> String {
+        format!("{}{}_{}", Self::OBO_IRI, self.ontology.prefix(), self.id)
+    }
+}
+
+/// Chemical properties of a reagent model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReagentProperties<'a> {
+    /// Purity of the reagent, as a percentage.
+    pub purity: f32,
+    /// CAS registry number of the reagent, e.g. `67-56-1` for methanol.
+    pub cas_code: &'a str,
+    /// Molecular formula of the reagent, e.g. `CH4O` for methanol.
+    pub molecular_formula: &'a str,
+}
+
+impl<'a> ReagentProperties<'a> {
+    /// Returns new reagent properties.
+    #[must_use]
+    pub const fn new(purity: f32, cas_code: &'a str, molecular_formula: &'a str) -> Self {
+        Self {
+            purity,
+            cas_code,
+            molecular_formula,
+        }
+    }
+}
+
+/// Deprecation of a reference model, written to the database when seeding.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemplateDeprecation {
+    /// Definition of the model replacing the deprecated one, in the same
+    /// table, if any.
+    pub replacement: Option<&'static TemplateDefinition>,
+}
+
+/// Code-side definition of a reference model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemplateDefinition {
+    /// Table in which the model is stored.
+    pub table: ModelTable,
+    /// Name of the model, unique within its namespace.
+    pub name: &'static str,
+    /// Description of the model.
+    pub description: &'static str,
+    /// Volume of the model in liters, for volumetric containers.
+    pub volume: Option<f32>,
+    /// Diameter of the model in millimeters, for beads.
+    pub diameter: Option<f32>,
+    /// MIME type of the model, for digital assets.
+    pub mime_type: Option<&'static str>,
+    /// Definition of the parent model, if any.
+    pub parent: Option<&'static TemplateDefinition>,
+    /// Definition of the sample source model, for samples.
+    pub sample_source: Option<&'static TemplateDefinition>,
+    /// Chemical properties of the model, for reagents.
+    pub reagent: Option<ReagentProperties<'static>>,
+    /// Ontology term the model is mapped to, if any.
+    pub ontology: Option<OntologyTerm>,
+    /// Deprecation of the model, if it should no longer be used.
+    pub deprecation: Option<TemplateDeprecation>,
+}
+
+impl TemplateDefinition {
+    /// Returns a new definition without any table-specific attribute.
+    #[must_use]
+    pub const fn new(table: ModelTable, name: &'static str, description: &'static str) -> Self {
+        Self {
+            table,
+            name,
+            description,
+            volume: None,
+            diameter: None,
+            mime_type: None,
+            parent: None,
+            sample_source: None,
+            reagent: None,
+            ontology: None,
+            deprecation: None,
+        }
+    }
+
+    /// Sets the volume of the model, in liters.
+    #[must_use]
+    pub const fn volume(mut self, volume: f32) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    /// Sets the diameter of the model, in millimeters.
+    #[must_use]
+    pub const fn diameter(mut self, diameter: f32) -> Self {
+        self.diameter = Some(diameter);
+        self
+    }
+
+    /// Sets the MIME type of the model.
+    #[must_use]
+    pub const fn mime_type(mut self, mime_type: &'static str) -> Self {
+        self.mime_type = Some(mime_type);
+        self
+    }
+
+    /// Sets the parent model.
+    #[must_use]
+    pub const fn parent(mut self, parent: &'static TemplateDefinition) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Sets the sample source model.
+    #[must_use]
+    pub const fn sample_source(mut self, sample_source: &'static TemplateDefinition) -> Self {
+        self.sample_source = Some(sample_source);
+        self
+    }
+
+    /// Sets the chemical properties of the model.
+    #[must_use]
+    pub const fn reagent(mut self, reagent: ReagentProperties<'static>) -> Self {
+        self.reagent = Some(reagent);
+        self
+    }
+
+    /// Sets the ontology term the model is mapped to.
+    #[must_use]
+    pub const fn ontology(mut self, term: OntologyTerm) -> Self {
+        self.ontology = Some(term);
+        self
+    }
+
+    /// Marks the model as deprecated, optionally in favour of another model of
+    /// the same table, see [`DeprecationPolicy`](crate::deprecation::DeprecationPolicy).
+    #[must_use]
+    pub const fn deprecated(mut self, replacement: Option<&'static TemplateDefinition>) -> Self {
+        self.deprecation = Some(TemplateDeprecation { replacement });
+        self
+    }
+
+    /// Returns the definitions of the models which have to exist before this
+    /// one can be created.
+    pub fn dependencies(&self) -> impl Iterator<Item = &'static TemplateDefinition> {
+        self.parent.into_iter().chain(self.sample_source)
+    }
+}
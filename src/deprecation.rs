@@ -0,0 +1,257 @@
+//! Submodule recording which reference models are deprecated, and resolving
+//! the lookups of deprecated models according to a [`DeprecationPolicy`].
+//!
+//! A model is deprecated by its definition, through
+//! [`TemplateDefinition::deprecated`], which is the only source of the
+//! deprecations: the lookups follow the definitions, see
+//! [`load_active_model`], and seeding writes them to the
+//! [`aps_templates_metadata`](crate::metadata::aps_templates_metadata) table
+//! for the tools reading the database, see [`template_deprecation_in`]. The
+//! deprecated model itself is kept so that the assets pointing to it remain
+//! valid.
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::context::TemplateContext;
+use crate::definitions::TemplateDefinition;
+use crate::errors::TemplateError;
+use crate::lookup::load_reference_model;
+use crate::metadata::CatalogMetadata;
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use diesel_builders::prelude::*;
+
+/// Prefix of the metadata keys recording the deprecated models, whose value is
+/// the name of the replacing model or an empty string.
+pub(crate) const DEPRECATED_KEY_PREFIX: &str = "deprecated:";
+
+/// How the templates behave when the model they look up is deprecated.
+///
+/// # Example
+///
+/// ```rust
+/// use aps::aps_bead_models::*;
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// // A bead superseded by the 3mm metal bead, declared for this example only.
+/// const OLD_BEAD_3MM: TemplateDefinition = TemplateDefinition::new(
+///     ModelTable::BeadModels,
+///     "Old Bead 3mm",
+///     "Bead of 3mm superseded by the metal one.",
+/// )
+/// .diameter(3.0)
+/// .deprecated(Some(&BEAD_3MM));
+///
+/// let test_user = user(&mut conn);
+/// let source = "[[templates]]\ntable = \"bead_models\"\nname = \"Old Bead 3mm\"\ndescription = \"Bead of 3mm superseded by the metal one.\"\ndiameter = 3.0\n";
+/// load_templates(&test_user, source, TemplateFormat::Toml, &mut conn).expect("Failed to create the old bead model");
+/// let namespace = reference_namespace(&test_user, &mut conn).expect("Failed to load the reference namespace");
+/// let bead = bead_3mm(&test_user, &mut conn).expect("Failed to create the bead model");
+///
+/// let warn = TemplateContext::new(&test_user);
+/// let old_bead = load_active_model::<bead_models::table, _>(&warn, &namespace, &OLD_BEAD_3MM, &mut conn)
+///     .expect("Failed to look up the deprecated bead model");
+/// assert!(old_bead.is_some_and(|old_bead| old_bead != bead));
+///
+/// let replace = TemplateContext::new(&test_user).deprecation_policy(DeprecationPolicy::Replace);
+/// assert_eq!(
+///     load_active_model(&replace, &namespace, &OLD_BEAD_3MM, &mut conn).expect("Failed to look up the replacing bead model"),
+///     Some(bead)
+/// );
+///
+/// let deny = TemplateContext::new(&test_user).deprecation_policy(DeprecationPolicy::Deny);
+/// let error = load_active_model::<bead_models::table, _>(&deny, &namespace, &OLD_BEAD_3MM, &mut conn)
+///     .expect_err("The deprecated bead model was returned");
+/// assert_eq!(error.to_string(), "`Old Bead 3mm` is deprecated in favour of `Metal Bead 3mm`");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DeprecationPolicy {
+    /// The deprecated model is returned silently.
+    Allow,
+    /// The deprecated model is returned, and a warning is logged.
+    #[default]
+    Warn,
+    /// The replacing model is returned in place of the deprecated one, and the
+    /// lookup fails if the model has no replacement.
+    Replace,
+    /// The lookup fails.
+    Deny,
+}
+
+/// Deprecation status of a stored reference model.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StoredDeprecation {
+    /// Name of the model replacing the deprecated one, if any.
+    pub replacement: Option<String>,
+}
+
+/// Returns the deprecation status of the model with the provided name in the
+/// default reference namespace, if it is deprecated.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// assert_eq!(template_deprecation(BEAD_3MM.name, &mut conn).expect("Failed to read the deprecation"), None);
+/// ```
+pub fn template_deprecation<C: CatalogMetadata>(
+    template_name: &str,
+    conn: &mut C,
+) -> Result<Option<StoredDeprecation>, TemplateError> {
+    template_deprecation_in(REFERENCE_NAMESPACE_NAME, template_name, conn)
+}
+
+/// Returns the deprecation status of the model with the provided name in the
+/// provided namespace, if it is deprecated.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `template_name` - The name of the model.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read.
+pub fn template_deprecation_in<C: CatalogMetadata>(
+    namespace_name: &str,
+    template_name: &str,
+    conn: &mut C,
+) -> Result<Option<StoredDeprecation>, TemplateError> {
+    Ok(conn
+        .read_metadata(
+            namespace_name,
+            &format!("{DEPRECATED_KEY_PREFIX}{template_name}"),
+        )?
+        .map(|replacement| StoredDeprecation {
+            replacement: (!replacement.is_empty()).then_some(replacement),
+        }))
+}
+
+/// Writes to the provided namespace the deprecations declared by the
+/// reference templates.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read or written.
+pub(crate) fn record_template_deprecations_in<C: CatalogMetadata>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<(), TemplateError> {
+    for definition in REFERENCE_TEMPLATES {
+        let Some(deprecation) = definition.deprecation else {
+            continue;
+        };
+        let replacement = deprecation.replacement.map(|replacement| replacement.name);
+        let stored = template_deprecation_in(namespace_name, definition.name, conn)?;
+        if stored.is_none_or(|stored| stored.replacement.as_deref() != replacement) {
+            conn.write_metadata(
+                namespace_name,
+                &format!("{DEPRECATED_KEY_PREFIX}{}", definition.name),
+                replacement.unwrap_or_default(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the model with the provided name from the provided namespace, if
+/// it exists, applying the [`DeprecationPolicy`] of the context when it is
+/// the model of a deprecated reference template, see [`load_active_model`].
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+/// * If the model is deprecated and the policy denies it, or replaces it
+///   while it has no replacement or the replacing model does not exist.
+pub fn load_active_reference_model<T, C>(
+    context: &TemplateContext<'_>,
+    namespace: &NestedModel<namespaces::table>,
+    name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<T>>, TemplateError>
+where
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<T, C>,
+{
+    match REFERENCE_TEMPLATES
+        .iter()
+        .find(|definition| definition.name == name)
+    {
+        Some(definition) => load_active_model(context, namespace, definition, conn),
+        None => load_reference_model(namespace, name, conn),
+    }
+}
+
+/// Returns the model of the provided definition from the provided namespace,
+/// if it exists, applying the [`DeprecationPolicy`] of the context when the
+/// definition is deprecated.
+///
+/// The policy follows the deprecation declared by the definition, so that the
+/// lookups need no access to the metadata table and keep the bounds of a
+/// plain lookup.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+/// * If the model is deprecated and the policy denies it, or replaces it
+///   while it has no replacement or the replacing model does not exist.
+pub fn load_active_model<T, C>(
+    context: &TemplateContext<'_>,
+    namespace: &NestedModel<namespaces::table>,
+    definition: &TemplateDefinition,
+    conn: &mut C,
+) -> Result<Option<NestedModel<T>>, TemplateError>
+where
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<T, C>,
+{
+    let name = definition.name;
+    let Some(deprecation) = definition.deprecation else {
+        return load_reference_model(namespace, name, conn);
+    };
+    if context.deprecation_policy == DeprecationPolicy::Allow {
+        return load_reference_model(namespace, name, conn);
+    }
+    let replacement = deprecation.replacement.map(|replacement| replacement.name);
+    let deprecated = || TemplateError::Deprecated {
+        template: name.to_owned(),
+        replacement: replacement.map(str::to_owned),
+    };
+    match context.deprecation_policy {
+        DeprecationPolicy::Allow | DeprecationPolicy::Warn => {
+            match replacement {
+                Some(replacement) => {
+                    log::warn!("`{name}` is deprecated in favour of `{replacement}`");
+                }
+                None => log::warn!("`{name}` is deprecated"),
+            }
+            load_reference_model(namespace, name, conn)
+        }
+        DeprecationPolicy::Replace => {
+            let Some(replacement) = replacement else {
+                return Err(deprecated());
+            };
+            load_reference_model(namespace, replacement, conn)?
+                .map(Some)
+                .ok_or_else(deprecated)
+        }
+        DeprecationPolicy::Deny => Err(deprecated()),
+    }
+}
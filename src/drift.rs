@@ -0,0 +1,196 @@
+//! Submodule comparing the stored reference models with their definitions.
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::definitions::TemplateDefinition;
+use crate::errors::TemplateError;
+use crate::lookup::{ReferenceReader, StoredModel};
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+
+/// Field of a reference model whose stored value differs from its definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDrift {
+    /// Name of the drifted field.
+    pub field: &'static str,
+    /// Value of the field in the definition.
+    pub expected: String,
+    /// Value of the field in the database.
+    pub stored: String,
+}
+
+/// Difference between a reference template and its row in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateDrift {
+    /// The model of the template has never been created.
+    Missing {
+        /// Name of the template.
+        template: &'static str,
+    },
+    /// The model of the template exists but some of its fields differ.
+    Changed {
+        /// Name of the template.
+        template: &'static str,
+        /// The drifted fields.
+        fields: Vec<FieldDrift>,
+    },
+}
+
+impl TemplateDrift {
+    /// Returns the name of the drifted template.
+    #[must_use]
+    pub fn template(&self) -> &'static str {
+        match self {
+            Self::Missing { template } | Self::Changed { template, .. } => template,
+        }
+    }
+}
+
+/// Formats an optional attribute, using `-` for a missing one.
+fn display<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "-".to_owned(), |value| value.to_string())
+}
+
+/// Returns the fields of the stored model differing from the definition.
+///
+/// The compared fields must be the ones hashed by
+/// [`catalog_hash`](crate::version::catalog_hash), so that a recorded
+/// catalog version stays a faithful summary of the last drift check.
+pub(crate) fn field_drifts(
+    definition: &TemplateDefinition,
+    stored: &StoredModel,
+) -> Vec<FieldDrift> {
+    let mut fields = Vec::new();
+    if stored.description != definition.description {
+        fields.push(FieldDrift {
+            field: "description",
+            expected: definition.description.to_owned(),
+            stored: stored.description.clone(),
+        });
+    }
+    if stored.volume != definition.volume {
+        fields.push(FieldDrift {
+            field: "volume",
+            expected: display(definition.volume),
+            stored: display(stored.volume),
+        });
+    }
+    if stored.diameter != definition.diameter {
+        fields.push(FieldDrift {
+            field: "diameter",
+            expected: display(definition.diameter),
+            stored: display(stored.diameter),
+        });
+    }
+    let expected_purity = definition.reagent.map(|reagent| reagent.purity);
+    if stored.purity != expected_purity {
+        fields.push(FieldDrift {
+            field: "purity",
+            expected: display(expected_purity),
+            stored: display(stored.purity),
+        });
+    }
+    let expected_cas_code = definition.reagent.map(|reagent| reagent.cas_code);
+    if stored.cas_code.as_deref() != expected_cas_code {
+        fields.push(FieldDrift {
+            field: "cas_code",
+            expected: display(expected_cas_code),
+            stored: display(stored.cas_code.as_deref()),
+        });
+    }
+    let expected_formula = definition.reagent.map(|reagent| reagent.molecular_formula);
+    if stored.molecular_formula.as_deref() != expected_formula {
+        fields.push(FieldDrift {
+            field: "molecular_formula",
+            expected: display(expected_formula),
+            stored: display(stored.molecular_formula.as_deref()),
+        });
+    }
+    if stored.mime_type.as_deref() != definition.mime_type {
+        fields.push(FieldDrift {
+            field: "mime_type",
+            expected: display(definition.mime_type),
+            stored: display(stored.mime_type.as_deref()),
+        });
+    }
+    fields
+}
+
+/// Returns the reference templates whose stored model is missing or differs
+/// from its definition in the default reference namespace.
+///
+/// Nothing is written to the database.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let drifts = check_drift(&mut conn).expect("Failed to check the drift");
+/// assert_eq!(drifts.len(), REFERENCE_TEMPLATES.len());
+///
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// let drifts = check_drift(&mut conn).expect("Failed to check the drift");
+/// assert!(drifts.is_empty());
+/// ```
+pub fn check_drift<C: ReferenceReader>(conn: &mut C) -> Result<Vec<TemplateDrift>, TemplateError> {
+    check_drift_in(REFERENCE_NAMESPACE_NAME, conn)
+}
+
+/// Returns the reference templates whose stored model is missing or differs
+/// from its definition in the provided namespace.
+///
+/// Nothing is written to the database.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+pub fn check_drift_in<C: ReferenceReader>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Vec<TemplateDrift>, TemplateError> {
+    let Some(namespace) = conn.find_namespace(namespace_name)? else {
+        return Ok(REFERENCE_TEMPLATES
+            .iter()
+            .map(|definition| TemplateDrift::Missing {
+                template: definition.name,
+            })
+            .collect());
+    };
+
+    let mut drifts = Vec::new();
+    for definition in REFERENCE_TEMPLATES {
+        match conn.find_stored_model(&namespace, definition.table, definition.name)? {
+            None => drifts.push(TemplateDrift::Missing {
+                template: definition.name,
+            }),
+            Some(stored) => {
+                let fields = field_drifts(definition, &stored);
+                if !fields.is_empty() {
+                    drifts.push(TemplateDrift::Changed {
+                        template: definition.name,
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    Ok(drifts)
+}
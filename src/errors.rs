@@ -0,0 +1,141 @@
+//! Submodule defining the errors returned by the templates.
+
+use crate::loader::DefinitionError;
+use diesel_builders::BuilderError;
+use validation_errors::ValidationError;
+
+/// Error returned when a template cannot be loaded or created.
+#[derive(Debug, thiserror::Error)]
+pub enum TemplateError {
+    /// One of the attributes of a template was rejected by the schema
+    /// validation.
+    #[error("Invalid `{attribute}` for `{template}`: {source}")]
+    Attribute {
+        /// Name of the template whose attribute was rejected.
+        template: String,
+        /// Name of the rejected attribute.
+        attribute: &'static str,
+        /// The underlying validation error.
+        #[source]
+        source: ValidationError,
+    },
+    /// The insertion of a missing model failed.
+    #[error("Failed to insert `{template}`: {source}")]
+    Insert {
+        /// Name of the template being inserted.
+        template: String,
+        /// The underlying builder error.
+        #[source]
+        source: BuilderError<ValidationError>,
+    },
+    /// The lookup of an existing model failed for a reason other than the
+    /// model not existing, e.g. a dropped connection.
+    #[error("Failed to look up `{template}`: {source}")]
+    Lookup {
+        /// Name of the model being looked up.
+        template: String,
+        /// The underlying database error.
+        #[source]
+        source: diesel::result::Error,
+    },
+    /// A model referenced by a compatibility rule does not exist, although it
+    /// should have been created beforehand.
+    #[error("`{template}` does not exist, so the rule {rule} cannot be created")]
+    MissingModel {
+        /// Description of the rule referencing the model.
+        rule: String,
+        /// Name of the missing model.
+        template: String,
+    },
+    /// The update of a stale model failed.
+    #[error("Failed to update `{template}`: {source}")]
+    Update {
+        /// Name of the template being updated.
+        template: String,
+        /// The underlying database error.
+        #[source]
+        source: diesel::result::Error,
+    },
+    /// The looked up model is deprecated, and the policy of the context
+    /// denies it or has no replacement to return in its place.
+    #[error(
+        "`{template}` is deprecated {}",
+        replacement.as_ref().map_or_else(
+            || "and has no replacement".to_owned(),
+            |replacement| format!("in favour of `{replacement}`"),
+        )
+    )]
+    Deprecated {
+        /// Name of the deprecated model.
+        template: String,
+        /// Name of the replacing model, if any.
+        replacement: Option<String>,
+    },
+    /// A lab-specific model, e.g. a purchased reagent lot, was to be stored in
+    /// the shared reference namespace.
+    #[error("`{template}` belongs to a lab inventory and cannot be stored in `{namespace}`")]
+    SharedNamespace {
+        /// Name of the lab-specific model.
+        template: String,
+        /// Name of the refused reference namespace.
+        namespace: &'static str,
+    },
+    /// A step of a template migration cannot be applied.
+    #[error("Failed to apply the template migration `{migration}`: {reason}")]
+    Migration {
+        /// Identifier of the failing migration.
+        migration: &'static str,
+        /// Why the step cannot be applied.
+        reason: String,
+    },
+    /// A file of template definitions is malformed or declares an invalid
+    /// template.
+    #[error("Invalid template definition: {0}")]
+    Definition(#[from] DefinitionError),
+    /// The serialization of an export failed.
+    #[error("Failed to serialize the export: {0}")]
+    Serialization(#[from] serde_json::Error),
+    /// A database operation surrounding the templates failed, e.g. opening or
+    /// committing a transaction.
+    #[error(transparent)]
+    Database(#[from] diesel::result::Error),
+}
+
+impl TemplateError {
+    /// Returns a closure wrapping a validation error of the provided attribute
+    /// of the provided template.
+    ///
+    /// Public only for the expansion of
+    /// [`reference_template!`](crate::reference_template) in other crates.
+    #[doc(hidden)]
+    pub fn attribute(
+        template: &str,
+        attribute: &'static str,
+    ) -> impl FnOnce(ValidationError) -> Self {
+        move |source| Self::Attribute {
+            template: template.to_owned(),
+            attribute,
+            source,
+        }
+    }
+
+    /// Returns a closure wrapping an insertion error of the provided template.
+    ///
+    /// Public only for the expansion of
+    /// [`reference_template!`](crate::reference_template) in other crates.
+    #[doc(hidden)]
+    pub fn insert(template: &str) -> impl FnOnce(BuilderError<ValidationError>) -> Self {
+        move |source| Self::Insert {
+            template: template.to_owned(),
+            source,
+        }
+    }
+
+    /// Returns a closure wrapping an update error of the provided template.
+    pub(crate) fn update(template: &str) -> impl FnOnce(diesel::result::Error) -> Self {
+        move |source| Self::Update {
+            template: template.to_owned(),
+            source,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Submodule exporting the reference catalog to formats usable without
+//! linking against Diesel.
+
+use serde::Serialize;
+
+use crate::asset_models::compatibility_rules::CompatibilityRule;
+use crate::asset_models::{REFERENCE_TEMPLATES, compatibility_rules};
+use crate::definitions::TemplateDefinition;
+use crate::errors::TemplateError;
+use crate::lookup::ReferenceReader;
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+
+pub mod graph;
+pub mod json;
+pub mod rdf;
+pub mod sql;
+pub use graph::*;
+pub use json::*;
+pub use rdf::*;
+pub use sql::*;
+
+/// Chemical properties of an exported reagent template.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedReagent {
+    /// Purity of the reagent, in percent.
+    pub purity: f32,
+    /// CAS registry number of the reagent.
+    pub cas_code: String,
+    /// Molecular formula of the reagent.
+    pub molecular_formula: String,
+}
+
+/// Exported reference template.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExportedTemplate {
+    /// Identifier of the stored model, only set by the online exports and
+    /// only for models which have been created.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Name of the table storing the model of the template.
+    pub table: &'static str,
+    /// Name of the template.
+    pub name: &'static str,
+    /// Description of the template.
+    pub description: String,
+    /// Volume of the model in liters, for volumetric containers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f32>,
+    /// Diameter of the model in millimeters, for beads.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diameter: Option<f32>,
+    /// MIME type of the model, for digital assets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Chemical properties of the model, for reagents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reagent: Option<ExportedReagent>,
+    /// Name of the parent template, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<&'static str>,
+    /// Name of the sample source template, for samples.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample_source: Option<&'static str>,
+    /// IRI of the ontology term the template is mapped to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ontology: Option<String>,
+}
+
+impl From<&TemplateDefinition> for ExportedTemplate {
+    fn from(definition: &TemplateDefinition) -> Self {
+        Self {
+            id: None,
+            table: definition.table.table_name(),
+            name: definition.name,
+            description: definition.description.to_owned(),
+            volume: definition.volume,
+            diameter: definition.diameter,
+            mime_type: definition.mime_type.map(str::to_owned),
+            reagent: definition.reagent.map(|reagent| ExportedReagent {
+                purity: reagent.purity,
+                cas_code: reagent.cas_code.to_owned(),
+                molecular_formula: reagent.molecular_formula.to_owned(),
+            }),
+            parent: definition.parent.map(|parent| parent.name),
+            sample_source: definition.sample_source.map(|source| source.name),
+            ontology: definition.ontology.map(|term| term.iri()),
+        }
+    }
+}
+
+/// Exported compatibility or containment edge between two templates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExportedEdge {
+    /// The two templates can be used together.
+    Compatible {
+        /// Name of the first template.
+        left: &'static str,
+        /// Name of the second template.
+        right: &'static str,
+    },
+    /// The container template can hold up to `quantity` of the contained
+    /// template.
+    Contains {
+        /// Name of the container template.
+        container: &'static str,
+        /// Name of the contained template.
+        contained: &'static str,
+        /// Maximum number of contained models fitting in the container.
+        quantity: i16,
+    },
+}
+
+impl From<&CompatibilityRule> for ExportedEdge {
+    fn from(rule: &CompatibilityRule) -> Self {
+        match *rule {
+            CompatibilityRule::Compatible { left, right } => Self::Compatible {
+                left: left.name,
+                right: right.name,
+            },
+            CompatibilityRule::Contains {
+                container,
+                contained,
+                quantity,
+            } => Self::Contains {
+                container: container.name,
+                contained: contained.name,
+                quantity,
+            },
+        }
+    }
+}
+
+/// Reference catalog as exported, with its templates and the edges between
+/// them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CatalogExport {
+    /// Every reference template, parents included.
+    pub templates: Vec<ExportedTemplate>,
+    /// Every compatibility and containment edge between the templates.
+    pub edges: Vec<ExportedEdge>,
+}
+
+impl CatalogExport {
+    /// Returns the export of the reference catalog as defined in the code,
+    /// without any database identifier.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use aps_templates::prelude::*;
+    ///
+    /// let export = CatalogExport::offline();
+    /// assert_eq!(export.templates.len(), REFERENCE_TEMPLATES.len());
+    /// assert!(export.templates.iter().all(|template| template.id.is_none()));
+    /// assert_eq!(export.edges.len(), compatibility_rules().count());
+    /// ```
+    #[must_use]
+    pub fn offline() -> Self {
+        Self {
+            templates: REFERENCE_TEMPLATES
+                .iter()
+                .map(ExportedTemplate::from)
+                .collect(),
+            edges: compatibility_rules().map(ExportedEdge::from).collect(),
+        }
+    }
+
+    /// Returns the export of the reference catalog, with the identifiers and
+    /// attributes of the models stored in the provided reference namespace.
+    ///
+    /// The templates whose model has been created are exported as stored, so
+    /// that a drifted model shows its stored description or volume, and the
+    /// others as defined in the code, without an identifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace_name` - The name of the reference namespace to read.
+    /// * `conn` - A mutable reference to the database connection where the
+    ///   reference models are stored.
+    ///
+    /// # Errors
+    ///
+    /// * If any of the lookups fails.
+    pub fn online_in<C: ReferenceReader>(
+        namespace_name: &str,
+        conn: &mut C,
+    ) -> Result<Self, TemplateError> {
+        let mut export = Self::offline();
+        let Some(namespace) = conn.find_namespace(namespace_name)? else {
+            return Ok(export);
+        };
+        for (template, definition) in export.templates.iter_mut().zip(REFERENCE_TEMPLATES) {
+            let Some(stored) =
+                conn.find_stored_model(&namespace, definition.table, definition.name)?
+            else {
+                continue;
+            };
+            template.id = Some(stored.id.to_string());
+            template.description = stored.description;
+            template.volume = stored.volume;
+            template.diameter = stored.diameter;
+            template.mime_type = stored.mime_type;
+            template.reagent = match (stored.purity, stored.cas_code, stored.molecular_formula) {
+                (Some(purity), Some(cas_code), Some(molecular_formula)) => Some(ExportedReagent {
+                    purity,
+                    cas_code,
+                    molecular_formula,
+                }),
+                _ => None,
+            };
+        }
+        Ok(export)
+    }
+
+    /// Returns the export of the reference catalog, with the identifiers and
+    /// attributes of the models stored in the default reference namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - A mutable reference to the database connection where the
+    ///   reference models are stored.
+    ///
+    /// # Errors
+    ///
+    /// * If any of the lookups fails.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use aps_test_utils::{aps_git_conn, user};
+    /// use aps_templates::prelude::*;
+    /// use diesel::RunQueryDsl;
+    /// let mut conn = aps_git_conn();
+    ///
+    /// let test_user = user(&mut conn);
+    /// let export = CatalogExport::online(&mut conn).expect("Failed to export the reference catalog");
+    /// assert!(export.templates.iter().all(|template| template.id.is_none()));
+    ///
+    /// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+    /// let export = CatalogExport::online(&mut conn).expect("Failed to export the reference catalog");
+    /// assert!(export.templates.iter().all(|template| template.id.is_some()));
+    /// assert_eq!(export, CatalogExport::online(&mut conn).unwrap());
+    ///
+    /// diesel::sql_query(format!("UPDATE namespaced_ownables SET description = 'Altered vial' WHERE name = '{}'", VIAL_1_5ML.name))
+    ///     .execute(&mut conn)
+    ///     .expect("Failed to alter the vial description");
+    /// let export = CatalogExport::online(&mut conn).expect("Failed to export the reference catalog");
+    /// let vial = export.templates.iter().find(|template| template.name == VIAL_1_5ML.name).unwrap();
+    /// assert_eq!(vial.description, "Altered vial");
+    /// ```
+    pub fn online<C: ReferenceReader>(conn: &mut C) -> Result<Self, TemplateError> {
+        Self::online_in(REFERENCE_NAMESPACE_NAME, conn)
+    }
+}
@@ -0,0 +1,137 @@
+//! Submodule rendering the compatibility rules as a graph, in DOT and
+//! Mermaid.
+//!
+//! Containment rules are drawn as arrows from the container to the contained
+//! model, labelled with the capacity of the container, while compatibility
+//! rules are drawn as dashed undirected edges.
+
+use std::fmt::Write;
+
+use crate::asset_models::compatibility_rules;
+use crate::asset_models::compatibility_rules::CompatibilityRule;
+use crate::definitions::TemplateDefinition;
+use crate::reference_namespaces::template_slug;
+
+/// Returns the models appearing in the compatibility rules, in order of first
+/// appearance.
+fn nodes() -> Vec<&'static TemplateDefinition> {
+    let mut nodes: Vec<&'static TemplateDefinition> = Vec::new();
+    for rule in compatibility_rules() {
+        let (first, second) = match *rule {
+            CompatibilityRule::Compatible { left, right } => (left, right),
+            CompatibilityRule::Contains {
+                container,
+                contained,
+                ..
+            } => (container, contained),
+        };
+        for definition in [first, second] {
+            if !nodes.iter().any(|node| node.name == definition.name) {
+                nodes.push(definition);
+            }
+        }
+    }
+    nodes
+}
+
+/// Returns the provided label as a quoted DOT string.
+fn dot_string(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns the compatibility rules as a Graphviz DOT digraph, with the
+/// capacities of the containers on the containment edges.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let dot = export_compatibility_dot();
+/// assert!(dot.starts_with("digraph compatibility_rules {"));
+/// assert!(dot.contains(r#""vial_rack_1_5ml_9x9" -> "vial_1_5ml" [label="81"];"#));
+/// assert!(dot.contains(r#"[label="Vial 1.5ml"];"#));
+/// assert!(dot.contains("style=dashed"));
+/// ```
+#[must_use]
+pub fn export_compatibility_dot() -> String {
+    let mut dot = String::from("digraph compatibility_rules {\n    rankdir=LR;\n");
+    for node in nodes() {
+        let _ = writeln!(
+            dot,
+            "    {} [label={}];",
+            dot_string(&template_slug(node.name)),
+            dot_string(node.name)
+        );
+    }
+    for rule in compatibility_rules() {
+        let _ = match *rule {
+            CompatibilityRule::Compatible { left, right } => writeln!(
+                dot,
+                "    {} -> {} [dir=none, style=dashed];",
+                dot_string(&template_slug(left.name)),
+                dot_string(&template_slug(right.name))
+            ),
+            CompatibilityRule::Contains {
+                container,
+                contained,
+                quantity,
+            } => writeln!(
+                dot,
+                "    {} -> {} [label=\"{quantity}\"];",
+                dot_string(&template_slug(container.name)),
+                dot_string(&template_slug(contained.name))
+            ),
+        };
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Returns the compatibility rules as a Mermaid flowchart, with the
+/// capacities of the containers on the containment edges.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let mermaid = export_compatibility_mermaid();
+/// assert!(mermaid.starts_with("flowchart LR\n"));
+/// assert!(mermaid.contains("vial_rack_1_5ml_9x9 -->|81| vial_1_5ml"));
+/// assert!(mermaid.contains(r#"vial_1_5ml["Vial 1.5ml"]"#));
+/// assert!(mermaid.contains(" -.- "));
+/// ```
+#[must_use]
+pub fn export_compatibility_mermaid() -> String {
+    let mut mermaid = String::from("flowchart LR\n");
+    for node in nodes() {
+        let _ = writeln!(
+            mermaid,
+            "    {}[\"{}\"]",
+            template_slug(node.name),
+            node.name.replace('"', "#quot;")
+        );
+    }
+    for rule in compatibility_rules() {
+        let _ = match *rule {
+            CompatibilityRule::Compatible { left, right } => writeln!(
+                mermaid,
+                "    {} -.- {}",
+                template_slug(left.name),
+                template_slug(right.name)
+            ),
+            CompatibilityRule::Contains {
+                container,
+                contained,
+                quantity,
+            } => writeln!(
+                mermaid,
+                "    {} -->|{quantity}| {}",
+                template_slug(container.name),
+                template_slug(contained.name)
+            ),
+        };
+    }
+    mermaid
+}
@@ -0,0 +1,60 @@
+//! Submodule exporting the reference catalog as JSON.
+
+use super::CatalogExport;
+use crate::errors::TemplateError;
+use crate::lookup::ReferenceReader;
+
+/// Returns the reference catalog as defined in the code as a JSON document,
+/// without any database identifier.
+///
+/// # Errors
+///
+/// * If the serialization fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let json = export_catalog_json_offline().expect("Failed to export the reference catalog");
+/// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+/// let templates = value["templates"].as_array().unwrap();
+/// assert_eq!(templates.len(), REFERENCE_TEMPLATES.len());
+/// let vial = templates.iter().find(|template| template["name"] == VIAL_1_5ML.name).unwrap();
+/// assert_eq!(vial["table"], "volumetric_container_models");
+/// assert!(vial["volume"].is_number());
+/// assert!(value["edges"].as_array().unwrap().iter().any(|edge| edge["kind"] == "contains"));
+/// ```
+pub fn export_catalog_json_offline() -> Result<String, TemplateError> {
+    Ok(serde_json::to_string_pretty(&CatalogExport::offline())?)
+}
+
+/// Returns the reference catalog as a JSON document, with the identifiers of
+/// the models stored in the default reference namespace.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+/// * If the serialization fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// let json = export_catalog_json(&mut conn).expect("Failed to export the reference catalog");
+/// let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+/// assert!(value["templates"].as_array().unwrap().iter().all(|template| template["id"].is_string()));
+/// ```
+pub fn export_catalog_json<C: ReferenceReader>(conn: &mut C) -> Result<String, TemplateError> {
+    Ok(serde_json::to_string_pretty(&CatalogExport::online(conn)?)?)
+}
@@ -0,0 +1,286 @@
+//! Submodule exporting the reference catalog as RDF, in Turtle and JSON-LD.
+//!
+//! Every template is published under its stable IRI, see
+//! [`template_iri`](crate::reference_namespaces::template_iri), typed with the
+//! class of its table and linked to its ontology term, if any, through
+//! `skos:closeMatch`.
+
+use std::fmt::{self, Display};
+
+use serde_json::{Map, Value, json};
+
+use crate::asset_models::compatibility_rules::CompatibilityRule;
+use crate::asset_models::{REFERENCE_TEMPLATES, compatibility_rules};
+use crate::definitions::TemplateDefinition;
+use crate::reference_namespaces::{REFERENCE_NAMESPACE_NAME, template_iri};
+
+/// IRI of the vocabulary describing the reference models.
+pub const APS_VOCABULARY_IRI: &str = "https://w3id.org/aps/vocabulary#";
+
+/// Prefixes used by the exported documents, with their IRIs.
+const PREFIXES: &[(&str, &str)] = &[
+    ("aps", APS_VOCABULARY_IRI),
+    ("dcterms", "http://purl.org/dc/terms/"),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("skos", "http://www.w3.org/2004/02/skos/core#"),
+    ("xsd", "http://www.w3.org/2001/XMLSchema#"),
+];
+
+/// Object of an RDF statement.
+enum Object {
+    /// A resource identified by its IRI.
+    Iri(String),
+    /// A literal, with its datatype if it is not a plain string.
+    Literal(String, Option<&'static str>),
+    /// A blank node with its own statements.
+    Node(Vec<(&'static str, Object)>),
+}
+
+/// Template as an RDF resource, with its statements.
+struct Resource {
+    /// IRI of the template.
+    iri: String,
+    /// Class of the template, as a compact IRI.
+    class: String,
+    /// Predicates, as compact IRIs, and objects of the statements.
+    statements: Vec<(&'static str, Object)>,
+}
+
+impl Resource {
+    /// Returns the resource of the provided definition, without its edges.
+    fn new(namespace_name: &str, definition: &TemplateDefinition) -> Self {
+        let iri = |definition: &TemplateDefinition| {
+            Object::Iri(template_iri(namespace_name, definition.name))
+        };
+        let float = |value: f32| Object::Literal(value.to_string(), Some("xsd:float"));
+        let string = |value: &str| Object::Literal(value.to_owned(), None);
+
+        let mut statements = vec![
+            ("rdfs:label", string(definition.name)),
+            ("dcterms:description", string(definition.description)),
+        ];
+        if let Some(volume) = definition.volume {
+            statements.push(("aps:volume", float(volume)));
+        }
+        if let Some(diameter) = definition.diameter {
+            statements.push(("aps:diameter", float(diameter)));
+        }
+        if let Some(mime_type) = definition.mime_type {
+            statements.push(("aps:mimeType", string(mime_type)));
+        }
+        if let Some(reagent) = definition.reagent {
+            statements.push(("aps:purity", float(reagent.purity)));
+            statements.push(("aps:casCode", string(reagent.cas_code)));
+            statements.push(("aps:molecularFormula", string(reagent.molecular_formula)));
+        }
+        if let Some(parent) = definition.parent {
+            statements.push(("aps:parent", iri(parent)));
+        }
+        if let Some(sample_source) = definition.sample_source {
+            statements.push(("aps:sampleSource", iri(sample_source)));
+        }
+        if let Some(term) = definition.ontology {
+            statements.push(("skos:closeMatch", Object::Iri(term.iri())));
+        }
+
+        Self {
+            iri: template_iri(namespace_name, definition.name),
+            class: format!("aps:{}", definition.table.class_name()),
+            statements,
+        }
+    }
+}
+
+/// Returns the resources of every reference template in the provided
+/// namespace, with the compatibility and containment edges starting from
+/// them.
+fn resources(namespace_name: &str) -> Vec<Resource> {
+    let mut resources: Vec<Resource> = REFERENCE_TEMPLATES
+        .iter()
+        .map(|definition| Resource::new(namespace_name, definition))
+        .collect();
+    for rule in compatibility_rules() {
+        let (subject, predicate, object) = match *rule {
+            CompatibilityRule::Compatible { left, right } => (
+                left,
+                "aps:compatibleWith",
+                Object::Iri(template_iri(namespace_name, right.name)),
+            ),
+            CompatibilityRule::Contains {
+                container,
+                contained,
+                quantity,
+            } => (
+                container,
+                "aps:canContain",
+                Object::Node(vec![
+                    (
+                        "aps:contained",
+                        Object::Iri(template_iri(namespace_name, contained.name)),
+                    ),
+                    (
+                        "aps:quantity",
+                        Object::Literal(quantity.to_string(), Some("xsd:short")),
+                    ),
+                ]),
+            ),
+        };
+        let subject = template_iri(namespace_name, subject.name);
+        if let Some(resource) = resources
+            .iter_mut()
+            .find(|resource| resource.iri == subject)
+        {
+            resource.statements.push((predicate, object));
+        }
+    }
+    resources
+}
+
+/// Writes the provided string as a Turtle literal.
+fn write_turtle_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for character in value.chars() {
+        match character {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            character => write!(f, "{character}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iri(iri) => write!(f, "<{iri}>"),
+            Self::Literal(value, datatype) => {
+                write_turtle_string(f, value)?;
+                if let Some(datatype) = datatype {
+                    write!(f, "^^{datatype}")?;
+                }
+                Ok(())
+            }
+            Self::Node(statements) => {
+                write!(f, "[ ")?;
+                for (index, (predicate, object)) in statements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " ; ")?;
+                    }
+                    write!(f, "{predicate} {object}")?;
+                }
+                write!(f, " ]")
+            }
+        }
+    }
+}
+
+/// Turtle document of the provided resources.
+struct Turtle<'a>(&'a [Resource]);
+
+impl Display for Turtle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (prefix, iri) in PREFIXES {
+            writeln!(f, "@prefix {prefix}: <{iri}> .")?;
+        }
+        for resource in self.0 {
+            writeln!(f)?;
+            write!(f, "<{}> a {}", resource.iri, resource.class)?;
+            for (predicate, object) in &resource.statements {
+                write!(f, " ;\n    {predicate} {object}")?;
+            }
+            writeln!(f, " .")?;
+        }
+        Ok(())
+    }
+}
+
+impl Object {
+    /// Returns the JSON-LD value of the object.
+    fn to_json_ld(&self) -> Value {
+        match self {
+            Self::Iri(iri) => json!({ "@id": iri }),
+            Self::Literal(value, None) => json!(value),
+            Self::Literal(value, Some(datatype)) => {
+                json!({ "@value": value, "@type": datatype })
+            }
+            Self::Node(statements) => Value::Object(json_ld_statements(statements)),
+        }
+    }
+}
+
+/// Returns the JSON-LD properties of the provided statements, grouping the
+/// objects sharing a predicate in an array.
+fn json_ld_statements(statements: &[(&'static str, Object)]) -> Map<String, Value> {
+    let mut properties = Map::new();
+    for (predicate, object) in statements {
+        let object = object.to_json_ld();
+        match properties.get_mut(*predicate) {
+            None => {
+                properties.insert((*predicate).to_owned(), object);
+            }
+            Some(Value::Array(objects)) => objects.push(object),
+            Some(previous) => *previous = json!([previous.take(), object]),
+        }
+    }
+    properties
+}
+
+/// Returns the reference catalog as a Turtle document, with every template
+/// published under its stable IRI in the default reference namespace.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let turtle = export_catalog_turtle();
+/// let methanol = template_iri(REFERENCE_NAMESPACE_NAME, METHANOL_HPLC.name);
+/// assert!(turtle.contains(&format!("<{methanol}> a aps:ReagentModel")));
+/// assert!(turtle.contains("skos:closeMatch <http://purl.obolibrary.org/obo/CHEBI_17790>"));
+/// assert!(turtle.contains("skos:closeMatch <http://purl.obolibrary.org/obo/ENVO_00001998>"));
+/// assert!(turtle.contains("aps:canContain [ aps:contained"));
+/// ```
+#[must_use]
+pub fn export_catalog_turtle() -> String {
+    Turtle(&resources(REFERENCE_NAMESPACE_NAME)).to_string()
+}
+
+/// Returns the reference catalog as a JSON-LD document, with every template
+/// published under its stable IRI in the default reference namespace.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let json_ld = export_catalog_json_ld();
+/// let value: serde_json::Value = serde_json::from_str(&json_ld).unwrap();
+/// let graph = value["@graph"].as_array().unwrap();
+/// assert_eq!(graph.len(), REFERENCE_TEMPLATES.len());
+/// let formic_acid = graph
+///     .iter()
+///     .find(|resource| resource["rdfs:label"] == FORMIC_ACID.name)
+///     .unwrap();
+/// assert_eq!(formic_acid["@type"], "aps:ReagentModel");
+/// assert_eq!(formic_acid["skos:closeMatch"]["@id"], "http://purl.obolibrary.org/obo/CHEBI_30751");
+/// ```
+#[must_use]
+pub fn export_catalog_json_ld() -> String {
+    let context: Map<String, Value> = PREFIXES
+        .iter()
+        .map(|(prefix, iri)| ((*prefix).to_owned(), json!(iri)))
+        .collect();
+    let graph: Vec<Value> = resources(REFERENCE_NAMESPACE_NAME)
+        .iter()
+        .map(|resource| {
+            let mut properties = json_ld_statements(&resource.statements);
+            properties.insert("@id".to_owned(), json!(resource.iri));
+            properties.insert("@type".to_owned(), json!(resource.class));
+            Value::Object(properties)
+        })
+        .collect();
+    format!("{:#}", json!({ "@context": context, "@graph": graph }))
+}
@@ -0,0 +1,575 @@
+//! Submodule generating an SQL script seeding the reference catalog, for
+//! deployments receiving their reference data through reviewed migrations
+//! rather than through the application.
+//!
+//! The script inserts the same rows as
+//! [`seed_reference_catalog`](crate::catalog::seed_reference_catalog) and
+//! [`init_compatibility_rules`](crate::asset_models::init_compatibility_rules),
+//! with the deterministic identifiers of the models when the context asks
+//! for them. Every statement is idempotent: the models are only inserted if
+//! no model with the same name exists in the namespace, and every insertion
+//! ends with an `ON CONFLICT` clause, so that the script can be applied to a
+//! database which has already been seeded, by the script or by the
+//! application. The rows of the models only skip conflicts on their
+//! identifier: a row conflicting on another unique constraint, such as the CAS
+//! code of a reagent model, fails the script rather than leaving a model
+//! without its specific row.
+//!
+//! The script starts with the pending
+//! [`TEMPLATE_MIGRATIONS`](crate::migrations::TEMPLATE_MIGRATIONS), so that
+//! renamed templates reuse their former models, and records them in the
+//! [`aps_templates_metadata`](crate::metadata::aps_templates_metadata) table
+//! as the application does. A migration moving a model which is not a
+//! reference reagent is rejected by the application, so the script stops at
+//! it, leaving it and the following ones pending. The script ends with the
+//! deprecations of the templates and the catalog version.
+
+use std::fmt::Write;
+use std::iter;
+
+use aps::aps_users::*;
+use diesel_builders::prelude::*;
+use rosetta_uuid::Uuid;
+
+use crate::asset_models::compatibility_rules::CompatibilityRule;
+use crate::asset_models::{REFERENCE_TEMPLATES, compatibility_rules};
+use crate::context::TemplateContext;
+use crate::definitions::{ModelTable, ReagentProperties, TemplateDefinition};
+use crate::deprecation::DEPRECATED_KEY_PREFIX;
+use crate::metadata::CREATE_METADATA_TABLE;
+use crate::migrations::{
+    MIGRATION_KEY_PREFIX, MigrationStep, TEMPLATE_MIGRATIONS, TemplateMigration,
+};
+use crate::version::{CATALOG_VERSION_KEY, CatalogVersion};
+
+/// Returns the provided string as an SQL literal.
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Returns the identifier of the provided user as an SQL literal.
+fn user_id(user: &User) -> String {
+    literal(&user.get_column::<users::id>().to_string())
+}
+
+/// Returns the SQL expression of the identifier of a new row, which is the
+/// provided deterministic identifier if any, and a random one otherwise.
+fn new_id(id: Option<Uuid>) -> String {
+    id.map_or_else(
+        || "gen_random_uuid()".to_owned(),
+        |id| literal(&id.to_string()),
+    )
+}
+
+/// Returns the SQL expression selecting the identifier of the namespace.
+fn namespace_id(namespace_name: &str) -> String {
+    format!(
+        "(SELECT id FROM namespaces WHERE name = {})",
+        literal(namespace_name)
+    )
+}
+
+/// Returns the SQL expression selecting the identifier of the model of the
+/// provided template.
+fn model_id(namespace_name: &str, definition: &TemplateDefinition) -> String {
+    format!(
+        "(SELECT id FROM namespaced_ownables WHERE namespace_id = {} AND name = {})",
+        namespace_id(namespace_name),
+        literal(definition.name)
+    )
+}
+
+/// Returns the SQL condition matching the model with the provided name stored
+/// in the provided table, on a row of `namespaced_ownables`.
+fn is_model(namespace_name: &str, table: ModelTable, name: &str) -> String {
+    format!(
+        "namespace_id = {} AND name = {}\n\
+         AND id IN (SELECT id FROM {})",
+        namespace_id(namespace_name),
+        literal(name),
+        table.table_name()
+    )
+}
+
+/// Returns the SQL condition telling whether the provided migration has not
+/// been applied to the namespace yet.
+fn is_pending(namespace_name: &str, migration: &TemplateMigration) -> String {
+    format!(
+        "NOT EXISTS (\n    \
+             SELECT 1 FROM aps_templates_metadata\n    \
+             WHERE namespace_name = {} AND key = {}\n\
+         )",
+        literal(namespace_name),
+        literal(&format!("{MIGRATION_KEY_PREFIX}{}", migration.id))
+    )
+}
+
+/// Returns the tables extending `namespaced_ownables` down to the provided
+/// table, from the most generic to the most specific.
+fn hierarchy(table: ModelTable) -> Vec<&'static str> {
+    let mut tables: Vec<_> = iter::successors(Some(table), |table| table.extended_table())
+        .map(ModelTable::table_name)
+        .chain(iter::once("asset_models"))
+        .collect();
+    tables.reverse();
+    tables
+}
+
+/// Returns the columns of the provided table set by the template, with the
+/// SQL expressions of their values.
+fn columns(
+    namespace_name: &str,
+    table: &str,
+    definition: &TemplateDefinition,
+) -> Vec<(&'static str, String)> {
+    let mut columns = Vec::new();
+    match table {
+        "asset_models" => {
+            if let Some(parent) = definition.parent {
+                columns.push(("parent_model_id", model_id(namespace_name, parent)));
+            }
+        }
+        "bead_models" => {
+            if let Some(diameter) = definition.diameter {
+                columns.push(("diameter", diameter.to_string()));
+            }
+        }
+        "digital_asset_models" => {
+            if let Some(mime_type) = definition.mime_type {
+                columns.push(("mime_type", literal(mime_type)));
+            }
+        }
+        "reagent_models" => {
+            if let Some(reagent) = definition.reagent {
+                columns.extend(reagent_columns(namespace_name, reagent));
+            }
+        }
+        "sample_models" => {
+            if let Some(sample_source) = definition.sample_source {
+                columns.push((
+                    "sample_source_model_id",
+                    model_id(namespace_name, sample_source),
+                ));
+            }
+        }
+        "volumetric_container_models" => {
+            if let Some(volume) = definition.volume {
+                columns.push(("volume", volume.to_string()));
+            }
+        }
+        _ => {}
+    }
+    columns
+}
+
+/// Returns the columns of the `reagent_models` table, with the SQL
+/// expressions of the namespace and of the provided properties.
+fn reagent_columns(
+    namespace_name: &str,
+    reagent: ReagentProperties<'_>,
+) -> [(&'static str, String); 4] {
+    [
+        ("namespace_id", namespace_id(namespace_name)),
+        ("purity", reagent.purity.to_string()),
+        ("cas_code", literal(reagent.cas_code)),
+        ("molecular_formula", literal(reagent.molecular_formula)),
+    ]
+}
+
+/// Returns the reference reagent properties a model is given when extended
+/// to the provided table, if the script supports the extension.
+fn extension(to: ModelTable, name: &str) -> Option<ReagentProperties<'static>> {
+    REFERENCE_TEMPLATES
+        .iter()
+        .find(|definition| definition.name == name && definition.table == to)
+        .and_then(|definition| definition.reagent)
+}
+
+/// Returns whether the script can apply the provided migration step.
+fn is_supported(step: MigrationStep) -> bool {
+    match step {
+        MigrationStep::Rename { .. } | MigrationStep::Describe { .. } => true,
+        MigrationStep::Extend { to, name, .. } => extension(to, name).is_some(),
+    }
+}
+
+/// Writes the statement creating the metadata table.
+fn write_metadata_table(sql: &mut String) {
+    let _ = writeln!(
+        sql,
+        "-- Metadata of the reference catalogs.\n{CREATE_METADATA_TABLE};\n"
+    );
+}
+
+/// Writes the statements inserting the namespace of the context.
+fn write_namespace(sql: &mut String, context: &TemplateContext<'_>) {
+    let _ = writeln!(
+        sql,
+        "-- Namespace `{}`.\n\
+         WITH new_ownables AS (\n    \
+             INSERT INTO ownables (id, owner_id, creator_id, editor_id)\n    \
+             SELECT {}, {}, {}, {}\n    \
+             WHERE NOT EXISTS (SELECT 1 FROM namespaces WHERE name = {name})\n    \
+             RETURNING id\n\
+         )\n\
+         INSERT INTO namespaces (id, name)\n\
+         SELECT id, {name} FROM new_ownables\n\
+         ON CONFLICT DO NOTHING;\n",
+        context.namespace_name,
+        new_id(context.namespace_id()),
+        user_id(context.owner),
+        user_id(context.creator),
+        user_id(context.editor),
+        name = literal(context.namespace_name),
+    );
+}
+
+/// Writes the statement applying the provided step of the provided
+/// migration, unless the migration was already applied.
+fn write_step(
+    sql: &mut String,
+    namespace_name: &str,
+    migration: &TemplateMigration,
+    step: MigrationStep,
+) {
+    let pending = is_pending(namespace_name, migration);
+    let _ = match step {
+        MigrationStep::Rename { table, from, to } => writeln!(
+            sql,
+            "-- Migration `{}`: rename `{from}` to `{to}`.\n\
+             UPDATE namespaced_ownables SET name = {}\n\
+             WHERE {}\n\
+             AND NOT EXISTS (\n    \
+                 SELECT 1 FROM namespaced_ownables\n    \
+                 WHERE namespace_id = {} AND name = {}\n\
+             )\n\
+             AND {pending};\n",
+            migration.id,
+            literal(to),
+            is_model(namespace_name, table, from),
+            namespace_id(namespace_name),
+            literal(to),
+        ),
+        MigrationStep::Describe {
+            table,
+            name,
+            description,
+        } => writeln!(
+            sql,
+            "-- Migration `{}`: describe `{name}`.\n\
+             UPDATE namespaced_ownables SET description = {}\n\
+             WHERE {}\n\
+             AND {pending};\n",
+            migration.id,
+            literal(description),
+            is_model(namespace_name, table, name),
+        ),
+        MigrationStep::Extend { from, to, name } => {
+            let Some(reagent) = extension(to, name) else {
+                return;
+            };
+            let (names, values): (Vec<_>, Vec<_>) = reagent_columns(namespace_name, reagent)
+                .into_iter()
+                .map(|(name, value)| (format!(", {name}"), format!(", {value}")))
+                .unzip();
+            writeln!(
+                sql,
+                "-- Migration `{}`: move `{name}` to `{}`.\n\
+                 INSERT INTO {} (id{})\n\
+                 SELECT id{} FROM namespaced_ownables\n\
+                 WHERE {}\n\
+                 AND {pending}\n\
+                 ON CONFLICT (id) DO NOTHING;\n",
+                migration.id,
+                to.table_name(),
+                to.table_name(),
+                names.concat(),
+                values.concat(),
+                is_model(namespace_name, from, name),
+            )
+        }
+    };
+}
+
+/// Writes the statement recording the provided migration as applied, unless
+/// one of the models it renames kept its former name because the new one
+/// was taken, in which case the application reports the conflict.
+fn write_applied(sql: &mut String, namespace_name: &str, migration: &TemplateMigration) {
+    let conflicts: Vec<_> = migration
+        .steps
+        .iter()
+        .filter_map(|step| match *step {
+            MigrationStep::Rename { table, from, .. } => Some(format!(
+                "NOT EXISTS (\n    \
+                     SELECT 1 FROM namespaced_ownables\n    \
+                     WHERE {}\n\
+                 )",
+                is_model(namespace_name, table, from).replace('\n', "\n    ")
+            )),
+            _ => None,
+        })
+        .collect();
+    let _ = writeln!(
+        sql,
+        "-- Migration `{}` applied.\n\
+         INSERT INTO aps_templates_metadata (namespace_name, key, value)\n\
+         SELECT {}, {}, {}{}\n\
+         ON CONFLICT DO NOTHING;\n",
+        migration.id,
+        literal(namespace_name),
+        literal(&format!("{MIGRATION_KEY_PREFIX}{}", migration.id)),
+        literal(&CatalogVersion::current().to_string()),
+        if conflicts.is_empty() {
+            String::new()
+        } else {
+            format!("\nWHERE {}", conflicts.join("\nAND "))
+        },
+    );
+}
+
+/// Writes the pending migrations the script can apply, in order, stopping at
+/// the first one it cannot.
+fn write_migrations(sql: &mut String, namespace_name: &str) {
+    for migration in TEMPLATE_MIGRATIONS {
+        if !migration.steps.iter().all(|step| is_supported(*step)) {
+            let _ = writeln!(
+                sql,
+                "-- Migration `{}` cannot be scripted, and the following ones are left pending.\n",
+                migration.id
+            );
+            return;
+        }
+        for step in migration.steps {
+            write_step(sql, namespace_name, migration, *step);
+        }
+        write_applied(sql, namespace_name, migration);
+    }
+}
+
+/// Writes the statement inserting the model of the provided template, along
+/// with the rows of every table it extends.
+fn write_template(
+    sql: &mut String,
+    context: &TemplateContext<'_>,
+    definition: &TemplateDefinition,
+) {
+    let namespace_name = context.namespace_name;
+    let _ = write!(
+        sql,
+        "-- Template `{}` in `{}`.\n\
+         WITH new_ownables AS (\n    \
+             INSERT INTO ownables (id, owner_id, creator_id, editor_id)\n    \
+             SELECT {}, {}, {}, {}\n    \
+             WHERE NOT EXISTS (\n        \
+                 SELECT 1 FROM namespaced_ownables\n        \
+                 WHERE namespace_id = {} AND name = {}\n    \
+             )\n    \
+             RETURNING id\n\
+         ),\n\
+         new_namespaced_ownables AS (\n    \
+             INSERT INTO namespaced_ownables (id, namespace_id, name, description)\n    \
+             SELECT id, {}, {}, {} FROM new_ownables\n    \
+             ON CONFLICT (id) DO NOTHING\n    \
+             RETURNING id\n\
+         )",
+        definition.name,
+        definition.table.table_name(),
+        new_id(context.template_id(definition.name)),
+        user_id(context.owner),
+        user_id(context.creator),
+        user_id(context.editor),
+        namespace_id(namespace_name),
+        literal(definition.name),
+        namespace_id(namespace_name),
+        literal(definition.name),
+        literal(definition.description),
+    );
+
+    let tables = hierarchy(definition.table);
+    let mut previous = "namespaced_ownables";
+    for (index, table) in tables.iter().enumerate() {
+        let (names, values): (Vec<_>, Vec<_>) = columns(namespace_name, table, definition)
+            .into_iter()
+            .map(|(name, value)| (format!(", {name}"), format!(", {value}")))
+            .unzip();
+        let names = names.concat();
+        let values = values.concat();
+        if index + 1 == tables.len() {
+            let _ = writeln!(
+                sql,
+                "\nINSERT INTO {table} (id{names})\n\
+                 SELECT id{values} FROM new_{previous}\n\
+                 ON CONFLICT (id) DO NOTHING;\n"
+            );
+        } else {
+            let _ = write!(
+                sql,
+                ",\n\
+                 new_{table} AS (\n    \
+                     INSERT INTO {table} (id{names})\n    \
+                     SELECT id{values} FROM new_{previous}\n    \
+                     ON CONFLICT (id) DO NOTHING\n    \
+                     RETURNING id\n\
+                 )"
+            );
+            previous = table;
+        }
+    }
+}
+
+/// Writes the definition and its dependencies, unless already written.
+fn write_with_dependencies(
+    sql: &mut String,
+    context: &TemplateContext<'_>,
+    definition: &'static TemplateDefinition,
+    written: &mut Vec<&'static str>,
+) {
+    if written.contains(&definition.name) {
+        return;
+    }
+    for dependency in definition.dependencies() {
+        write_with_dependencies(sql, context, dependency, written);
+    }
+    write_template(sql, context, definition);
+    written.push(definition.name);
+}
+
+/// Writes the statement inserting the provided compatibility rule.
+fn write_rule(sql: &mut String, context: &TemplateContext<'_>, rule: &CompatibilityRule) {
+    let namespace_name = context.namespace_name;
+    let creator = user_id(context.creator);
+    let _ = match *rule {
+        CompatibilityRule::Compatible { left, right } => writeln!(
+            sql,
+            "-- {rule}.\n\
+             INSERT INTO asset_compatibility_rules (left_asset_model_id, right_asset_model_id, creator_id)\n\
+             SELECT {}, {}, {creator}\n\
+             ON CONFLICT DO NOTHING;\n",
+            model_id(namespace_name, left),
+            model_id(namespace_name, right),
+        ),
+        CompatibilityRule::Contains {
+            container,
+            contained,
+            quantity,
+        } => writeln!(
+            sql,
+            "-- {rule}.\n\
+             INSERT INTO container_compatibility_rules (container_model_id, contained_asset_model_id, quantity, creator_id)\n\
+             SELECT {}, {}, {quantity}, {creator}\n\
+             ON CONFLICT DO NOTHING;\n",
+            model_id(namespace_name, container),
+            model_id(namespace_name, contained),
+        ),
+    };
+}
+
+/// Writes the statement recording the provided metadata entry, replacing the
+/// stored value if `replace` is set.
+fn write_metadata(sql: &mut String, namespace_name: &str, key: &str, value: &str, replace: bool) {
+    let _ = writeln!(
+        sql,
+        "INSERT INTO aps_templates_metadata (namespace_name, key, value)\n\
+         VALUES ({}, {}, {})\n\
+         ON CONFLICT (namespace_name, key) DO {};\n",
+        literal(namespace_name),
+        literal(key),
+        literal(value),
+        if replace {
+            "UPDATE SET value = EXCLUDED.value"
+        } else {
+            "NOTHING"
+        },
+    );
+}
+
+/// Writes the statements recording the deprecations of the templates and the
+/// catalog version.
+///
+/// Unlike the application, the script does not update the models differing
+/// from their templates, so it only records the catalog version in a
+/// namespace which has none: a namespace seeded by an earlier catalog keeps
+/// its version until the application reconciles it.
+fn write_catalog_metadata(sql: &mut String, namespace_name: &str) {
+    for definition in REFERENCE_TEMPLATES {
+        let Some(deprecation) = definition.deprecation else {
+            continue;
+        };
+        let _ = writeln!(sql, "-- Deprecation of `{}`.", definition.name);
+        write_metadata(
+            sql,
+            namespace_name,
+            &format!("{DEPRECATED_KEY_PREFIX}{}", definition.name),
+            deprecation
+                .replacement
+                .map_or("", |replacement| replacement.name),
+            true,
+        );
+    }
+    let _ = writeln!(sql, "-- Catalog version.");
+    write_metadata(
+        sql,
+        namespace_name,
+        CATALOG_VERSION_KEY,
+        &CatalogVersion::current().to_string(),
+        false,
+    );
+}
+
+/// Returns an idempotent SQL script seeding the reference catalog and its
+/// compatibility rules in the namespace of the provided context.
+///
+/// # Arguments
+///
+/// * `context` - The namespace, users and identifiers to seed the catalog
+///   with, or simply the user owning, creating and editing the inserted rows.
+///
+/// # Example
+///
+/// ```rust
+/// use aps::aps_volumetric_container_models::*;
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel::connection::SimpleConnection;
+/// use diesel_builders::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let context = TemplateContext::new(&test_user).deterministic_ids(true);
+/// let sql = export_seed_sql(context);
+/// assert!(sql.contains("INSERT INTO namespaces (id, name)"));
+/// assert!(sql.contains(&template_uuid(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name).to_string()));
+/// assert!(!export_seed_sql(&test_user).contains(&template_uuid(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name).to_string()));
+///
+/// conn.batch_execute(&sql).expect("Failed to apply the seed script");
+/// conn.batch_execute(&sql).expect("Failed to apply the seed script twice");
+/// let lab = TemplateContext::new(&test_user).namespace_name("lab_reference");
+/// conn.batch_execute(&export_seed_sql(lab)).expect("Failed to seed a second namespace");
+/// assert!(check_drift(&mut conn).expect("Failed to check the drift").is_empty());
+/// assert!(plan_reference_catalog(&mut conn).expect("Failed to plan the reference catalog").is_noop());
+/// assert!(apply_template_migrations(&mut conn).expect("Failed to apply the migrations").is_empty());
+/// assert_eq!(catalog_status(&mut conn).expect("Failed to read the catalog version"), CatalogStatus::Current);
+/// let vial = vial_1_5ml(context, &mut conn).expect("Failed to load the vial model");
+/// assert_eq!(
+///     vial.get_column::<volumetric_container_models::id>().to_owned(),
+///     template_uuid(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name)
+/// );
+/// init_compatibility_rules(context, &mut conn).expect("Failed to initialize the compatibility rules");
+/// ```
+#[must_use]
+pub fn export_seed_sql<'a>(context: impl Into<TemplateContext<'a>>) -> String {
+    let context = context.into();
+    let mut sql = String::new();
+    write_metadata_table(&mut sql);
+    write_namespace(&mut sql, &context);
+    write_migrations(&mut sql, context.namespace_name);
+    let mut written = Vec::new();
+    for definition in REFERENCE_TEMPLATES {
+        write_with_dependencies(&mut sql, &context, definition, &mut written);
+    }
+    for rule in compatibility_rules() {
+        write_rule(&mut sql, &context, rule);
+    }
+    write_catalog_metadata(&mut sql, context.namespace_name);
+    sql
+}
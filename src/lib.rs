@@ -1,9 +1,53 @@
 #![doc = include_str!("../README.md")]
 
 pub mod asset_models;
+pub mod catalog;
+pub mod context;
+pub mod definitions;
+pub mod deprecation;
+pub mod drift;
+pub mod errors;
+pub mod export;
+pub mod loader;
+mod lookup;
+mod macros;
+pub mod metadata;
+pub mod migrations;
+pub mod plan;
+pub mod reconcile;
 pub mod reference_namespaces;
+pub mod template;
+pub mod version;
 
 pub mod prelude {
     pub use crate::asset_models::*;
+    pub use crate::catalog::*;
+    pub use crate::context::*;
+    pub use crate::definitions::*;
+    pub use crate::deprecation::*;
+    pub use crate::drift::*;
+    pub use crate::errors::*;
+    pub use crate::export::*;
+    pub use crate::loader::*;
+    pub use crate::lookup::{ReferenceReader, StoredModel};
+    pub use crate::metadata::CatalogMetadata;
+    pub use crate::migrations::*;
+    pub use crate::plan::*;
+    pub use crate::reconcile::*;
     pub use crate::reference_namespaces::*;
+    pub use crate::template::*;
+    pub use crate::version::*;
+}
+
+/// Items used by the code generated by [`reference_template!`], which is
+/// expanded in downstream crates and therefore cannot name them otherwise.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::context::WithContextId;
+    pub use crate::deprecation::load_active_reference_model;
+    pub use crate::lookup::get_or_insert;
+    pub use aps;
+    pub use diesel;
+    pub use diesel_builders::{self, TableBuilder, prelude::*};
+    pub use paste::paste;
 }
@@ -0,0 +1,912 @@
+//! Submodule loading reference templates declared in TOML or YAML files, so
+//! that new reference models can be added without writing Rust.
+//!
+//! A file lists its templates under the `templates` key, each tagged with the
+//! table storing its model:
+//!
+//! ```toml
+//! [[templates]]
+//! table = "volumetric_container_models"
+//! name = "Vial 4ml"
+//! description = "Vial of 4ml, used to store extracts"
+//! volume = 0.004
+//!
+//! [[templates]]
+//! table = "container_models"
+//! name = "Vial Rack 4ml"
+//! description = "Rack for storing vials of 4ml"
+//! parent = "Standard Rack"
+//! ```
+//!
+//! The parent of a template is referenced by name, and must be either a
+//! built-in template or a template declared earlier in the same file.
+
+use std::fmt::{self, Display};
+
+use serde::Deserialize;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::context::{TemplateContext, WithContextId};
+use crate::definitions::{ModelTable, TemplateDefinition};
+use crate::errors::TemplateError;
+use crate::lookup::{ReferenceReader, get_or_insert};
+use crate::reference_namespaces::reference_namespace;
+use aps::aps_bead_models::*;
+use aps::aps_container_models::*;
+use aps::aps_container_sealer_models::*;
+use aps::aps_digital_asset_models::*;
+use aps::aps_namespaces::*;
+use aps::aps_ownables::*;
+use aps::aps_packaging_models::*;
+use aps::aps_physical_asset_models::*;
+use aps::aps_reagent_models::*;
+use aps::aps_users::*;
+use aps::aps_volumetric_container_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+use rosetta_uuid::Uuid;
+
+/// Format of a file of template definitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TemplateFormat {
+    /// A TOML file, with the templates as an array of tables.
+    Toml,
+    /// A YAML file, with the templates as a sequence of mappings.
+    Yaml,
+}
+
+/// Error returned when a file of template definitions is malformed or
+/// declares an invalid template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionError {
+    /// Line of the file at which the error was found, starting from one, if
+    /// known.
+    pub line: Option<usize>,
+    /// Description of the error.
+    pub message: String,
+}
+
+impl Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+/// Chemical properties of a reagent declared in a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclaredReagent {
+    /// Purity of the reagent, as a percentage.
+    pub purity: f32,
+    /// CAS registry number of the reagent.
+    pub cas_code: String,
+    /// Molecular formula of the reagent.
+    pub molecular_formula: String,
+}
+
+/// Parent of a template declared in a file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeclaredParent {
+    /// A built-in template.
+    BuiltIn(&'static TemplateDefinition),
+    /// A template declared earlier in the same file, by its position among
+    /// the parsed templates.
+    Declared(usize),
+}
+
+/// Definition of a template declared in a file.
+///
+/// Unlike a [`TemplateDefinition`], it owns its attributes, so that parsing a
+/// file allocates nothing living longer than the returned definitions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclaredDefinition {
+    /// Table in which the model is stored.
+    pub table: ModelTable,
+    /// Name of the model, unique within its namespace.
+    pub name: String,
+    /// Description of the model.
+    pub description: String,
+    /// Volume of the model in liters, for volumetric containers.
+    pub volume: Option<f32>,
+    /// Diameter of the model in millimeters, for beads.
+    pub diameter: Option<f32>,
+    /// MIME type of the model, for digital assets.
+    pub mime_type: Option<String>,
+    /// Chemical properties of the model, for reagents.
+    pub reagent: Option<DeclaredReagent>,
+    /// Parent of the model, if any.
+    pub parent: Option<DeclaredParent>,
+}
+
+impl From<&TemplateDefinition> for DeclaredDefinition {
+    fn from(definition: &TemplateDefinition) -> Self {
+        Self {
+            table: definition.table,
+            name: definition.name.to_owned(),
+            description: definition.description.to_owned(),
+            volume: definition.volume,
+            diameter: definition.diameter,
+            mime_type: definition.mime_type.map(str::to_owned),
+            reagent: definition.reagent.map(|reagent| DeclaredReagent {
+                purity: reagent.purity,
+                cas_code: reagent.cas_code.to_owned(),
+                molecular_formula: reagent.molecular_formula.to_owned(),
+            }),
+            parent: definition.parent.map(DeclaredParent::BuiltIn),
+        }
+    }
+}
+
+/// Template as declared in a file, tagged with the table of its model.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "table", rename_all = "snake_case", deny_unknown_fields)]
+enum DeclaredTemplate {
+    /// A model of the `physical_asset_models` table.
+    PhysicalAssetModels {
+        name: String,
+        description: String,
+        parent: Option<String>,
+    },
+    /// A model of the `container_models` table.
+    ContainerModels {
+        name: String,
+        description: String,
+        parent: Option<String>,
+    },
+    /// A model of the `volumetric_container_models` table.
+    VolumetricContainerModels {
+        name: String,
+        description: String,
+        volume: f32,
+        parent: Option<String>,
+    },
+    /// A model of the `container_sealer_models` table.
+    ContainerSealerModels {
+        name: String,
+        description: String,
+        parent: Option<String>,
+    },
+    /// A model of the `packaging_models` table.
+    PackagingModels {
+        name: String,
+        description: String,
+        parent: Option<String>,
+    },
+    /// A model of the `bead_models` table.
+    BeadModels {
+        name: String,
+        description: String,
+        diameter: f32,
+        parent: Option<String>,
+    },
+    /// A model of the `digital_asset_models` table.
+    DigitalAssetModels {
+        name: String,
+        description: String,
+        mime_type: String,
+        parent: Option<String>,
+    },
+    /// A model of the `reagent_models` table.
+    ReagentModels {
+        name: String,
+        description: String,
+        purity: f32,
+        cas_code: String,
+        molecular_formula: String,
+        parent: Option<String>,
+    },
+}
+impl DeclaredTemplate {
+    /// Returns the table, name, description and parent name of the template.
+    fn header(&self) -> (ModelTable, &str, &str, Option<&str>) {
+        let (table, name, description, parent) = match self {
+            Self::PhysicalAssetModels {
+                name,
+                description,
+                parent,
+            } => (ModelTable::PhysicalAssetModels, name, description, parent),
+            Self::ContainerModels {
+                name,
+                description,
+                parent,
+            } => (ModelTable::ContainerModels, name, description, parent),
+            Self::VolumetricContainerModels {
+                name,
+                description,
+                parent,
+                ..
+            } => (
+                ModelTable::VolumetricContainerModels,
+                name,
+                description,
+                parent,
+            ),
+            Self::ContainerSealerModels {
+                name,
+                description,
+                parent,
+            } => (ModelTable::ContainerSealerModels, name, description, parent),
+            Self::PackagingModels {
+                name,
+                description,
+                parent,
+            } => (ModelTable::PackagingModels, name, description, parent),
+            Self::BeadModels {
+                name,
+                description,
+                parent,
+                ..
+            } => (ModelTable::BeadModels, name, description, parent),
+            Self::DigitalAssetModels {
+                name,
+                description,
+                parent,
+                ..
+            } => (ModelTable::DigitalAssetModels, name, description, parent),
+            Self::ReagentModels {
+                name,
+                description,
+                parent,
+                ..
+            } => (ModelTable::ReagentModels, name, description, parent),
+        };
+        (table, name, description, parent.as_deref())
+    }
+
+    /// Checks the table-specific attributes of the template, returning the
+    /// reason of their rejection if any.
+    fn check(&self) -> Result<(), String> {
+        match self {
+            Self::VolumetricContainerModels { name, volume, .. } if *volume <= 0.0 => {
+                Err(format!("the volume of `{name}` must be positive"))
+            }
+            Self::BeadModels { name, diameter, .. } if *diameter <= 0.0 => {
+                Err(format!("the diameter of `{name}` must be positive"))
+            }
+            Self::ReagentModels { name, purity, .. } if *purity <= 0.0 || *purity > 100.0 => {
+                Err(format!("the purity of `{name}` must be within (0, 100]"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the definition of the checked template, with the provided
+    /// parent.
+    fn into_definition(self, parent: Option<DeclaredParent>) -> DeclaredDefinition {
+        let mut definition = DeclaredDefinition {
+            table: self.header().0,
+            name: String::new(),
+            description: String::new(),
+            volume: None,
+            diameter: None,
+            mime_type: None,
+            reagent: None,
+            parent,
+        };
+        (definition.name, definition.description) = match self {
+            Self::PhysicalAssetModels {
+                name, description, ..
+            }
+            | Self::ContainerModels {
+                name, description, ..
+            }
+            | Self::ContainerSealerModels {
+                name, description, ..
+            }
+            | Self::PackagingModels {
+                name, description, ..
+            } => (name, description),
+            Self::VolumetricContainerModels {
+                name,
+                description,
+                volume,
+                ..
+            } => {
+                definition.volume = Some(volume);
+                (name, description)
+            }
+            Self::BeadModels {
+                name,
+                description,
+                diameter,
+                ..
+            } => {
+                definition.diameter = Some(diameter);
+                (name, description)
+            }
+            Self::DigitalAssetModels {
+                name,
+                description,
+                mime_type,
+                ..
+            } => {
+                definition.mime_type = Some(mime_type);
+                (name, description)
+            }
+            Self::ReagentModels {
+                name,
+                description,
+                purity,
+                cas_code,
+                molecular_formula,
+                ..
+            } => {
+                definition.reagent = Some(DeclaredReagent {
+                    purity,
+                    cas_code,
+                    molecular_formula,
+                });
+                (name, description)
+            }
+        };
+        definition
+    }
+}
+
+/// Root of a file of template definitions.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateFile<T> {
+    /// The declared templates, in order.
+    templates: Vec<T>,
+}
+
+/// Returns the line of the provided byte offset of the source, starting from
+/// one.
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+/// Seed locating an entry of the `templates` sequence of a YAML file.
+///
+/// Deserializing the entry at the provided position fails, so that the error
+/// carries the location at which the parser found it, whatever the layout of
+/// the file.
+#[derive(Debug, Clone, Copy)]
+enum EntryLocator {
+    /// Looks for the `templates` key of the root mapping.
+    Root(usize),
+    /// Skips the entries preceding the provided position.
+    Templates(usize),
+    /// Fails on the located entry.
+    Entry,
+}
+
+impl<'de> DeserializeSeed<'de> for EntryLocator {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        match self {
+            Self::Root(_) => deserializer.deserialize_map(self),
+            Self::Templates(_) => deserializer.deserialize_seq(self),
+            Self::Entry => deserializer.deserialize_any(self),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for EntryLocator {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Root(_) => f.write_str("a mapping with a `templates` key"),
+            Self::Templates(_) => f.write_str("a sequence of templates"),
+            Self::Entry => f.write_str("the located template"),
+        }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let Self::Root(position) = self else {
+            return Err(de::Error::invalid_type(de::Unexpected::Map, &self));
+        };
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "templates" {
+                return map.next_value_seed(Self::Templates(position));
+            }
+            map.next_value::<IgnoredAny>()?;
+        }
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let Self::Templates(position) = self else {
+            return Err(de::Error::invalid_type(de::Unexpected::Seq, &self));
+        };
+        for _ in 0..position {
+            if seq.next_element::<IgnoredAny>()?.is_none() {
+                return Ok(());
+            }
+        }
+        seq.next_element_seed(Self::Entry)?;
+        Ok(())
+    }
+}
+
+/// Returns the line at which the template with the provided position is
+/// declared in the provided source, starting from one, if known.
+fn declaration_line(source: &str, format: TemplateFormat, position: usize) -> Option<usize> {
+    match format {
+        TemplateFormat::Toml => toml::from_str::<TemplateFile<toml::Spanned<IgnoredAny>>>(source)
+            .ok()?
+            .templates
+            .get(position)
+            .map(|declared| line_at(source, declared.span().start)),
+        TemplateFormat::Yaml => EntryLocator::Root(position)
+            .deserialize(serde_yaml::Deserializer::from_str(source))
+            .err()?
+            .location()
+            .map(|location| location.line()),
+    }
+}
+
+/// Returns the templates declared in the provided source.
+fn declarations(
+    source: &str,
+    format: TemplateFormat,
+) -> Result<Vec<DeclaredTemplate>, DefinitionError> {
+    Ok(match format {
+        TemplateFormat::Toml => {
+            toml::from_str::<TemplateFile<DeclaredTemplate>>(source)
+                .map_err(|error| DefinitionError {
+                    line: error.span().map(|span| line_at(source, span.start)),
+                    message: error.message().to_owned(),
+                })?
+                .templates
+        }
+        TemplateFormat::Yaml => {
+            serde_yaml::from_str::<TemplateFile<DeclaredTemplate>>(source)
+                .map_err(|error| DefinitionError {
+                    line: error.location().map(|location| location.line()),
+                    message: error.to_string(),
+                })?
+                .templates
+        }
+    })
+}
+
+/// Returns the templates declared in the provided source.
+///
+/// The returned definitions own their attributes: a parent declared in the
+/// same file is referenced by its position among them, and a built-in parent
+/// by its [`TemplateDefinition`].
+///
+/// # Arguments
+///
+/// * `source` - The content of the file of template definitions.
+/// * `format` - The format of the file.
+///
+/// # Errors
+///
+/// * If the file is malformed, or a template has an unknown table, a missing
+///   or unknown attribute, or an attribute of the wrong type.
+/// * If a template has an empty name, a non-positive volume, diameter or
+///   purity, or the name of a built-in or previously declared template.
+/// * If the parent of a template is neither a built-in template nor a
+///   template declared earlier in the file.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let source = r#"
+/// [[templates]]
+/// table = "volumetric_container_models"
+/// name = "Vial 4ml"
+/// description = "Vial of 4ml, used to store extracts"
+/// volume = 0.004
+///
+/// [[templates]]
+/// table = "container_models"
+/// name = "Vial Rack 4ml"
+/// description = "Rack for storing vials of 4ml"
+/// parent = "Standard Rack"
+///
+/// [[templates]]
+/// table = "container_models"
+/// name = "Small Vial Rack 4ml"
+/// description = "Rack for storing a few vials of 4ml"
+/// parent = "Vial Rack 4ml"
+/// "#;
+/// let templates = parse_templates(source, TemplateFormat::Toml).expect("Failed to parse the templates");
+/// assert_eq!(templates[0].volume, Some(0.004));
+/// assert_eq!(templates[1].parent, Some(DeclaredParent::BuiltIn(&STANDARD_RACK)));
+/// assert_eq!(templates[2].parent, Some(DeclaredParent::Declared(1)));
+///
+/// let error = parse_templates("templates:\n  - table: bead_models\n    name: Bead 5mm\n    description: Bead\n", TemplateFormat::Yaml)
+///     .unwrap_err();
+/// assert_eq!(error.line, Some(2));
+/// assert!(error.message.contains("diameter"));
+///
+/// // The line of an invalid template does not depend on the layout of the file.
+/// let block = "
+/// # Trays of the lab
+/// templates: # declared by hand
+///     - table: physical_asset_models # the first tray
+///       name: Sample Tray
+///       description: Tray holding the samples
+///
+///     # the same tray, declared again
+///     - table: physical_asset_models
+///       name: Sample Tray
+///       description: Another tray holding the samples
+/// ";
+/// let error = parse_templates(block, TemplateFormat::Yaml).unwrap_err();
+/// assert_eq!(error.line, Some(9));
+/// assert!(error.message.contains("already defined"));
+///
+/// let flow = "
+/// templates: [
+///   {table: physical_asset_models, name: Sample Tray, description: Tray holding the samples},
+///   {table: physical_asset_models, name: Sample Tray, description: Another tray holding the samples}
+/// ]
+/// ";
+/// assert_eq!(parse_templates(flow, TemplateFormat::Yaml).unwrap_err().line, Some(4));
+/// ```
+pub fn parse_templates(
+    source: &str,
+    format: TemplateFormat,
+) -> Result<Vec<DeclaredDefinition>, DefinitionError> {
+    let mut templates: Vec<DeclaredDefinition> = Vec::new();
+    for (position, declared) in declarations(source, format)?.into_iter().enumerate() {
+        let error = |message: String| DefinitionError {
+            line: declaration_line(source, format, position),
+            message,
+        };
+        let (_, name, _, parent) = declared.header();
+
+        if name.trim().is_empty() {
+            return Err(error("the name of a template must not be empty".to_owned()));
+        }
+        if REFERENCE_TEMPLATES
+            .iter()
+            .any(|template| template.name == name)
+            || templates.iter().any(|template| template.name == name)
+        {
+            return Err(error(format!("the template `{name}` is already defined")));
+        }
+        declared.check().map_err(error)?;
+        let parent = match parent {
+            None => None,
+            Some(parent_name) => Some(
+                match templates
+                    .iter()
+                    .position(|template| template.name == parent_name)
+                {
+                    Some(position) => DeclaredParent::Declared(position),
+                    None => DeclaredParent::BuiltIn(
+                        REFERENCE_TEMPLATES
+                            .iter()
+                            .find(|template| template.name == parent_name)
+                            .ok_or_else(|| {
+                                error(format!(
+                                    "the parent `{parent_name}` of `{name}` is neither a built-in template nor declared before it"
+                                ))
+                            })?,
+                    ),
+                },
+            ),
+        };
+        templates.push(declared.into_definition(parent));
+    }
+    Ok(templates)
+}
+
+/// Connection able to create the models of every table supported by the
+/// template files.
+///
+/// This trait is implemented for every connection satisfying the insert
+/// bounds of all the supported tables, and only exists so that the loader
+/// does not need to repeat them.
+pub trait DefinitionWriter: ReferenceReader + Connection {
+    /// Inserts the model of the provided definition in the provided namespace,
+    /// as a child of the model with the provided identifier if any, and
+    /// returns its identifier.
+    ///
+    /// # Errors
+    ///
+    /// * If the table of the definition is not supported by the template
+    ///   files.
+    /// * If the definition lacks an attribute required by its table, e.g. the
+    ///   chemical properties of a reagent.
+    /// * If any of the attributes is rejected by the schema validation.
+    /// * If the insertion fails.
+    fn insert_definition(
+        &mut self,
+        context: &TemplateContext<'_>,
+        namespace: &NestedModel<namespaces::table>,
+        definition: &DeclaredDefinition,
+        parent_id: Option<Uuid>,
+    ) -> Result<Uuid, TemplateError>;
+}
+
+/// Inserts a model in the provided table, setting the common attributes and
+/// then the table-specific ones.
+macro_rules! insert_model {
+    (
+        $conn:expr, $context:expr, $namespace:expr, $definition:expr, $parent_id:expr,
+        $table:ident $(, $setter:ident($attribute:literal) = $value:expr)*
+    ) => {{
+        let name = $definition.name.as_str();
+        let builder = $table::table::builder()
+            .try_name(name)
+            .map_err(TemplateError::attribute(name, "name"))?
+            .try_description($definition.description.as_str())
+            .map_err(TemplateError::attribute(name, "description"))?;
+        $(
+            let builder = builder
+                .$setter($value)
+                .map_err(TemplateError::attribute(name, $attribute))?;
+        )*
+        let builder = match $parent_id {
+            Some(parent_id) => builder
+                .try_parent_model_id(parent_id)
+                .map_err(TemplateError::attribute(name, "parent_model_id"))?,
+            None => builder,
+        };
+        builder
+            .creator_id($context.creator.get_column::<users::id>())
+            .editor_id($context.editor.get_column::<users::id>())
+            .owner_id($context.owner.get_column::<users::id>())
+            .namespace_id($namespace.get_column::<namespaces::id>())
+            .template_id($context, name)
+            .insert_nested($conn)
+            .map_err(TemplateError::insert(name))?
+            .get_column::<$table::id>()
+            .to_owned()
+    }};
+}
+
+impl<C> DefinitionWriter for C
+where
+    C: ReferenceReader + Connection,
+    TableBuilder<physical_asset_models::table>: Insert<C>,
+    TableBuilder<container_models::table>: Insert<C>,
+    TableBuilder<volumetric_container_models::table>: Insert<C>,
+    TableBuilder<container_sealer_models::table>: Insert<C>,
+    TableBuilder<packaging_models::table>: Insert<C>,
+    TableBuilder<bead_models::table>: Insert<C>,
+    TableBuilder<digital_asset_models::table>: Insert<C>,
+    TableBuilder<reagent_models::table>: Insert<C>,
+{
+    fn insert_definition(
+        &mut self,
+        context: &TemplateContext<'_>,
+        namespace: &NestedModel<namespaces::table>,
+        definition: &DeclaredDefinition,
+        parent_id: Option<Uuid>,
+    ) -> Result<Uuid, TemplateError> {
+        let id = match definition.table {
+            ModelTable::PhysicalAssetModels => {
+                insert_model!(
+                    self,
+                    context,
+                    namespace,
+                    definition,
+                    parent_id,
+                    physical_asset_models
+                )
+            }
+            ModelTable::ContainerModels => {
+                insert_model!(
+                    self,
+                    context,
+                    namespace,
+                    definition,
+                    parent_id,
+                    container_models
+                )
+            }
+            ModelTable::VolumetricContainerModels => insert_model!(
+                self,
+                context,
+                namespace,
+                definition,
+                parent_id,
+                volumetric_container_models,
+                try_volume("volume") = definition
+                    .volume
+                    .ok_or_else(|| incomplete(definition, "volume"))?
+            ),
+            ModelTable::ContainerSealerModels => {
+                insert_model!(
+                    self,
+                    context,
+                    namespace,
+                    definition,
+                    parent_id,
+                    container_sealer_models
+                )
+            }
+            ModelTable::PackagingModels => {
+                insert_model!(
+                    self,
+                    context,
+                    namespace,
+                    definition,
+                    parent_id,
+                    packaging_models
+                )
+            }
+            ModelTable::BeadModels => insert_model!(
+                self,
+                context,
+                namespace,
+                definition,
+                parent_id,
+                bead_models,
+                try_diameter("diameter") = definition
+                    .diameter
+                    .ok_or_else(|| incomplete(definition, "diameter"))?
+            ),
+            ModelTable::DigitalAssetModels => insert_model!(
+                self,
+                context,
+                namespace,
+                definition,
+                parent_id,
+                digital_asset_models,
+                try_mime_type("mime_type") = definition
+                    .mime_type
+                    .as_deref()
+                    .ok_or_else(|| incomplete(definition, "MIME type"))?
+            ),
+            ModelTable::ReagentModels => {
+                let reagent = definition
+                    .reagent
+                    .as_ref()
+                    .ok_or_else(|| incomplete(definition, "chemical properties"))?;
+                insert_model!(
+                    self,
+                    context,
+                    namespace,
+                    definition,
+                    parent_id,
+                    reagent_models,
+                    try_purity("purity") = reagent.purity,
+                    try_cas_code("cas_code") = reagent.cas_code.as_str(),
+                    try_molecular_formula("molecular_formula") = reagent.molecular_formula.as_str()
+                )
+            }
+            table => {
+                return Err(TemplateError::Definition(DefinitionError {
+                    line: None,
+                    message: format!(
+                        "templates of the `{}` table cannot be declared in a file",
+                        table.table_name()
+                    ),
+                }));
+            }
+        };
+        Ok(id)
+    }
+}
+
+/// Model created or found for a template declared in a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedTemplate {
+    /// Definition of the template.
+    pub definition: DeclaredDefinition,
+    /// Identifier of its model.
+    pub id: Uuid,
+}
+
+/// Returns the error of a definition lacking the provided attribute, which
+/// its table requires.
+fn incomplete(definition: &DeclaredDefinition, attribute: &str) -> TemplateError {
+    TemplateError::Definition(DefinitionError {
+        line: None,
+        message: format!("the definition of `{}` has no {attribute}", definition.name),
+    })
+}
+
+/// Returns the model of the provided definition, creating it and its
+/// ancestors if they do not exist.
+///
+/// The parents declared in the file are looked up among the provided
+/// templates, which were loaded before it.
+fn get_or_create_definition<C: DefinitionWriter>(
+    context: &TemplateContext<'_>,
+    namespace: &NestedModel<namespaces::table>,
+    definition: &DeclaredDefinition,
+    loaded: &[LoadedTemplate],
+    conn: &mut C,
+) -> Result<Uuid, TemplateError> {
+    get_or_insert(
+        |conn: &mut C| {
+            Ok(conn
+                .find_stored_model(namespace, definition.table, &definition.name)?
+                .map(|stored| stored.id))
+        },
+        |conn: &mut C| {
+            let parent_id = match definition.parent {
+                None => None,
+                Some(DeclaredParent::BuiltIn(parent)) => Some(get_or_create_definition(
+                    context,
+                    namespace,
+                    &DeclaredDefinition::from(parent),
+                    loaded,
+                    conn,
+                )?),
+                Some(DeclaredParent::Declared(position)) => Some(loaded[position].id),
+            };
+            conn.insert_definition(context, namespace, definition, parent_id)
+        },
+        conn,
+    )
+}
+
+/// Parses the templates declared in the provided source and returns their
+/// models, creating them if they do not exist, exactly like the built-in
+/// get-or-create functions do.
+///
+/// # Arguments
+///
+/// * `context` - The namespace and users to create the models with, or
+///   simply the creating user.
+/// * `source` - The content of the file of template definitions.
+/// * `format` - The format of the file.
+/// * `conn` - A mutable reference to the database connection where the
+///   models will be created.
+///
+/// # Errors
+///
+/// * If the file declares an invalid template, see [`parse_templates`].
+/// * If the lookup or the creation of any model fails.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let source = "
+/// templates:
+///   - table: volumetric_container_models
+///     name: Vial 4ml
+///     description: Vial of 4ml, used to store extracts
+///     volume: 0.004
+///   - table: container_models
+///     name: Vial Rack 4ml
+///     description: Rack for storing vials of 4ml
+///     parent: Standard Rack
+/// ";
+/// let loaded1 = load_templates(&test_user, source, TemplateFormat::Yaml, &mut conn)
+///     .expect("Failed to load the templates");
+/// let loaded2 = load_templates(&test_user, source, TemplateFormat::Yaml, &mut conn)
+///     .expect("Failed to load the templates");
+/// assert_eq!(loaded1.len(), 2);
+/// assert_eq!(
+///     loaded1.iter().map(|loaded| loaded.id).collect::<Vec<_>>(),
+///     loaded2.iter().map(|loaded| loaded.id).collect::<Vec<_>>()
+/// );
+/// assert!(find_standard_rack(&mut conn).expect("Failed to look up the standard rack").is_some());
+/// ```
+pub fn load_templates<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
+    source: &str,
+    format: TemplateFormat,
+    conn: &mut C,
+) -> Result<Vec<LoadedTemplate>, TemplateError>
+where
+    C: DefinitionWriter,
+    TableBuilder<namespaces::table>: Insert<C>,
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+{
+    let context = context.into();
+    let definitions = parse_templates(source, format)?;
+    let namespace = reference_namespace(context, conn)?;
+    let mut loaded = Vec::with_capacity(definitions.len());
+    for definition in definitions {
+        let id = get_or_create_definition(&context, &namespace, &definition, &loaded, conn)?;
+        loaded.push(LoadedTemplate { definition, id });
+    }
+    Ok(loaded)
+}
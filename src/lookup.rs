@@ -0,0 +1,518 @@
+//! Submodule providing the lookups shared by every template.
+
+use crate::definitions::ModelTable;
+use crate::errors::TemplateError;
+use aps::aps_ball_mill_machine_models::*;
+use aps::aps_bead_models::*;
+use aps::aps_centrifuge_models::*;
+use aps::aps_container_models::*;
+use aps::aps_container_sealer_models::*;
+use aps::aps_digital_asset_models::*;
+use aps::aps_freeze_dryer_models::*;
+use aps::aps_freezer_models::*;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_packaging_models::*;
+use aps::aps_personal_protective_equipment_models::*;
+use aps::aps_phone_device_models::*;
+use aps::aps_physical_asset_models::*;
+use aps::aps_pipette_tip_models::*;
+use aps::aps_reagent_models::*;
+use aps::aps_sample_models::*;
+use aps::aps_sample_source_models::*;
+use aps::aps_volume_measuring_device_models::*;
+use aps::aps_volumetric_container_models::*;
+use aps::aps_weighing_device_models::*;
+use diesel::{Connection, OptionalExtension};
+use diesel_builders::prelude::*;
+use rosetta_uuid::Uuid;
+
+/// Converts the result of a lookup into an `Option`, telling apart a missing
+/// row from an actual failure.
+pub(crate) fn optional<M>(
+    template: &str,
+    result: Result<M, diesel::result::Error>,
+) -> Result<Option<M>, TemplateError> {
+    result.optional().map_err(|source| TemplateError::Lookup {
+        template: template.to_owned(),
+        source,
+    })
+}
+
+/// Returns the row found by `lookup`, inserting it with `insert` if missing.
+///
+/// Two concurrent callers may both miss the lookup and both try to insert the
+/// same row, in which case one of them fails on the unique constraint. The
+/// insertion therefore runs in a savepoint, and when it fails the lookup is
+/// repeated: if the row of the winner is found it is returned in place of the
+/// error, and the surrounding transaction, if any, is left usable.
+///
+/// # Errors
+///
+/// * If the lookup fails.
+/// * If the insertion fails and no concurrent caller inserted the row.
+pub fn get_or_insert<M, C>(
+    lookup: impl Fn(&mut C) -> Result<Option<M>, TemplateError>,
+    insert: impl FnOnce(&mut C) -> Result<M, TemplateError>,
+    conn: &mut C,
+) -> Result<M, TemplateError>
+where
+    C: Connection,
+{
+    if let Some(existing) = lookup(conn)? {
+        return Ok(existing);
+    }
+
+    match conn.transaction(insert) {
+        Ok(inserted) => Ok(inserted),
+        Err(error) => lookup(conn)?.ok_or(error),
+    }
+}
+
+/// Returns the namespace with the provided name, if it exists.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the namespace not existing.
+pub(crate) fn load_namespace<C>(
+    name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<namespaces::table>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+{
+    optional(
+        name,
+        <(namespaces::name,)>::load_nested_first((name,), conn),
+    )
+}
+
+/// Returns the model with the provided name in the provided namespace, if it
+/// exists.
+///
+/// # Errors
+///
+/// * If the lookup fails for any reason other than the model not existing.
+pub fn load_reference_model<T, C>(
+    namespace: &NestedModel<namespaces::table>,
+    name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<T>>, TemplateError>
+where
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<T, C>,
+{
+    optional(
+        name,
+        <(
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        )>::load_nested_first((namespace.get_column::<namespaces::id>(), (name,)), conn),
+    )
+}
+
+/// Returns the model with the provided name in the namespace with the provided
+/// name, if both exist.
+///
+/// # Errors
+///
+/// * If either lookup fails for any reason other than the row not existing.
+pub fn find_reference_model<T, C>(
+    namespace_name: &str,
+    name: &str,
+    conn: &mut C,
+) -> Result<Option<NestedModel<T>>, TemplateError>
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<T, C>,
+{
+    match load_namespace(namespace_name, conn)? {
+        Some(namespace) => load_reference_model(&namespace, name, conn),
+        None => Ok(None),
+    }
+}
+
+/// Attributes of a reference model as stored in the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredModel {
+    /// Identifier of the model.
+    pub id: Uuid,
+    /// Description of the model.
+    pub description: String,
+    /// Volume of the model in liters, for volumetric containers.
+    pub volume: Option<f32>,
+    /// Diameter of the model in millimeters, for beads.
+    pub diameter: Option<f32>,
+    /// MIME type of the model, for digital assets.
+    pub mime_type: Option<String>,
+    /// Purity of the model as a percentage, for reagents.
+    pub purity: Option<f32>,
+    /// CAS registry number of the model, for reagents.
+    pub cas_code: Option<String>,
+    /// Molecular formula of the model, for reagents.
+    pub molecular_formula: Option<String>,
+}
+
+impl StoredModel {
+    fn new(id: Uuid, description: impl Into<String>) -> Self {
+        Self {
+            id,
+            description: description.into(),
+            volume: None,
+            diameter: None,
+            mime_type: None,
+            purity: None,
+            cas_code: None,
+            molecular_formula: None,
+        }
+    }
+
+    fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = Some(volume);
+        self
+    }
+
+    fn with_diameter(mut self, diameter: f32) -> Self {
+        self.diameter = Some(diameter);
+        self
+    }
+
+    fn with_reagent(
+        mut self,
+        purity: f32,
+        cas_code: impl Into<String>,
+        molecular_formula: impl Into<String>,
+    ) -> Self {
+        self.purity = Some(purity);
+        self.cas_code = Some(cas_code.into());
+        self.molecular_formula = Some(molecular_formula.into());
+        self
+    }
+
+    fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+}
+
+/// Connection able to look up the models of every [`ModelTable`].
+///
+/// This trait is implemented for every connection satisfying the lookup
+/// bounds of all the templates, and only exists so that the functions working
+/// across the whole catalog do not need to repeat them.
+pub trait ReferenceReader {
+    /// Returns the namespace with the provided name, if it exists.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup fails for any reason other than the namespace not
+    ///   existing.
+    fn find_namespace(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<NestedModel<namespaces::table>>, TemplateError>;
+
+    /// Returns the stored attributes of the model with the provided name in
+    /// the provided table and namespace, if it exists.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup fails for any reason other than the model not existing.
+    fn find_stored_model(
+        &mut self,
+        namespace: &NestedModel<namespaces::table>,
+        table: ModelTable,
+        name: &str,
+    ) -> Result<Option<StoredModel>, TemplateError>;
+}
+
+impl<C> ReferenceReader for C
+where
+    (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<ball_mill_machine_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<bead_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<centrifuge_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<container_sealer_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<digital_asset_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freeze_dryer_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<freezer_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<packaging_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<personal_protective_equipment_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<phone_device_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<physical_asset_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<pipette_tip_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<reagent_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<sample_source_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volume_measuring_device_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<volumetric_container_models::table, C>,
+    (
+        namespaced_ownables::namespace_id,
+        (namespaced_ownables::name,),
+    ): LoadNestedFirst<weighing_device_models::table, C>,
+{
+    fn find_namespace(
+        &mut self,
+        name: &str,
+    ) -> Result<Option<NestedModel<namespaces::table>>, TemplateError> {
+        load_namespace(name, self)
+    }
+
+    fn find_stored_model(
+        &mut self,
+        namespace: &NestedModel<namespaces::table>,
+        table: ModelTable,
+        name: &str,
+    ) -> Result<Option<StoredModel>, TemplateError> {
+        Ok(match table {
+            ModelTable::BallMillMachineModels => {
+                load_reference_model::<ball_mill_machine_models::table, C>(namespace, name, self)?
+                    .map(|model| {
+                        StoredModel::new(
+                            model.get_column::<namespaced_ownables::id>().to_owned(),
+                            model.get_column::<namespaced_ownables::description>(),
+                        )
+                    })
+            }
+            ModelTable::BeadModels => {
+                load_reference_model::<bead_models::table, C>(namespace, name, self)?.map(|model| {
+                    StoredModel::new(
+                        model.get_column::<namespaced_ownables::id>().to_owned(),
+                        model.get_column::<namespaced_ownables::description>(),
+                    )
+                    .with_diameter(model.get_column::<bead_models::diameter>().to_owned())
+                })
+            }
+            ModelTable::CentrifugeModels => load_reference_model::<centrifuge_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::ContainerModels => load_reference_model::<container_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::ContainerSealerModels => {
+                load_reference_model::<container_sealer_models::table, C>(namespace, name, self)?
+                    .map(|model| {
+                        StoredModel::new(
+                            model.get_column::<namespaced_ownables::id>().to_owned(),
+                            model.get_column::<namespaced_ownables::description>(),
+                        )
+                    })
+            }
+            ModelTable::DigitalAssetModels => {
+                load_reference_model::<digital_asset_models::table, C>(namespace, name, self)?.map(
+                    |model| {
+                        StoredModel::new(
+                            model.get_column::<namespaced_ownables::id>().to_owned(),
+                            model.get_column::<namespaced_ownables::description>(),
+                        )
+                        .with_mime_type(model.get_column::<digital_asset_models::mime_type>())
+                    },
+                )
+            }
+            ModelTable::FreezeDryerModels => load_reference_model::<freeze_dryer_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::FreezerModels => load_reference_model::<freezer_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::PackagingModels => load_reference_model::<packaging_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::PersonalProtectiveEquipmentModels => load_reference_model::<
+                personal_protective_equipment_models::table,
+                C,
+            >(namespace, name, self)?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::PhoneDeviceModels => load_reference_model::<phone_device_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::PhysicalAssetModels => {
+                load_reference_model::<physical_asset_models::table, C>(namespace, name, self)?.map(
+                    |model| {
+                        StoredModel::new(
+                            model.get_column::<namespaced_ownables::id>().to_owned(),
+                            model.get_column::<namespaced_ownables::description>(),
+                        )
+                    },
+                )
+            }
+            ModelTable::PipetteTipModels => load_reference_model::<pipette_tip_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::ReagentModels => load_reference_model::<reagent_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+                .with_reagent(
+                    model.get_column::<reagent_models::purity>().to_owned(),
+                    model.get_column::<reagent_models::cas_code>(),
+                    model.get_column::<reagent_models::molecular_formula>(),
+                )
+            }),
+            ModelTable::SampleModels => load_reference_model::<sample_models::table, C>(
+                namespace, name, self,
+            )?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::SampleSourceModels => {
+                load_reference_model::<sample_source_models::table, C>(namespace, name, self)?.map(
+                    |model| {
+                        StoredModel::new(
+                            model.get_column::<namespaced_ownables::id>().to_owned(),
+                            model.get_column::<namespaced_ownables::description>(),
+                        )
+                    },
+                )
+            }
+            ModelTable::VolumeMeasuringDeviceModels => load_reference_model::<
+                volume_measuring_device_models::table,
+                C,
+            >(namespace, name, self)?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+            }),
+            ModelTable::VolumetricContainerModels => load_reference_model::<
+                volumetric_container_models::table,
+                C,
+            >(namespace, name, self)?
+            .map(|model| {
+                StoredModel::new(
+                    model.get_column::<namespaced_ownables::id>().to_owned(),
+                    model.get_column::<namespaced_ownables::description>(),
+                )
+                .with_volume(
+                    model
+                        .get_column::<volumetric_container_models::volume>()
+                        .to_owned(),
+                )
+            }),
+            ModelTable::WeighingDeviceModels => {
+                load_reference_model::<weighing_device_models::table, C>(namespace, name, self)?
+                    .map(|model| {
+                        StoredModel::new(
+                            model.get_column::<namespaced_ownables::id>().to_owned(),
+                            model.get_column::<namespaced_ownables::description>(),
+                        )
+                    })
+            }
+        })
+    }
+}
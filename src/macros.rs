@@ -0,0 +1,303 @@
+//! Submodule defining the macro declaring reference templates, so that
+//! downstream crates can add their own templates without copying the bounds
+//! and the lookup-then-insert body of the built-in ones.
+
+/// Declares a reference template, generating the same items as the built-in
+/// templates of [`asset_models`](crate::asset_models).
+///
+/// For a template declared as `pub fn vial_4ml`, the macro generates:
+///
+/// * `VIAL_4ML`, the [`TemplateDefinition`](crate::definitions::TemplateDefinition)
+///   of the model.
+/// * `vial_4ml`, returning the model and creating it if it does not exist.
+/// * `find_vial_4ml`, returning the model if it exists.
+/// * `find_vial_4ml_in`, returning the model from the provided namespace if it
+///   exists.
+/// * `Vial4ml`, the zero-sized type implementing
+///   [`AssetModelTemplate`](crate::template::AssetModelTemplate).
+/// * If a `test` block follows the template, a test checking that `vial_4ml`
+///   is idempotent, compiled with `cfg(test)`.
+///
+/// The table is given by name, followed by the table-specific attributes in
+/// any order: `volume` for the volumetric container models, `diameter` for the
+/// bead models and `mime_type` for the digital asset models. Each attribute is
+/// set through the [`TemplateDefinition`](crate::definitions::TemplateDefinition)
+/// method and the builder setter of the same name, so any other attribute, or
+/// one the table lacks, fails to compile. Templates with a parent, a sample
+/// source or chemical properties need other models to be created first, and
+/// are not supported: declare them in a [template file](crate::loader) or
+/// write their function by hand.
+///
+/// The `test` block provides the expressions returning the database connection
+/// of the generated test and, from it, its user, e.g. the functions of
+/// `aps_test_utils`. The test is generated in the module invoking the macro,
+/// where the expressions are resolved, and belongs to the crate invoking it:
+/// it runs with the `cargo test` of that crate, as a unit test when the macro
+/// is invoked in a library or binary and as part of the test when invoked in
+/// an integration test. It never runs when the macro is invoked in a doctest,
+/// where `cfg(test)` is not set.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+/// use aps_test_utils::{aps_git_conn, user};
+///
+/// aps_templates::reference_template! {
+///     /// Returns the vial 4ml model, creating it if it does not exist.
+///     pub fn vial_4ml {
+///         table: volumetric_container_models,
+///         name: "Vial 4ml",
+///         description: "Vial of 4 ml used for extracts storage",
+///         volume: 0.004,
+///     }
+///     test {
+///         conn: aps_git_conn(),
+///         user: user,
+///     }
+/// }
+///
+/// fn main() {
+///     let mut conn = aps_git_conn();
+///
+///     assert_eq!(VIAL_4ML.volume, Some(0.004));
+///     assert!(find_vial_4ml(&mut conn).expect("Failed to look up the vial 4ml model").is_none());
+///     let test_user = user(&mut conn);
+///     let vial1 = vial_4ml(&test_user, &mut conn).expect("Failed to create vial model");
+///     let vial2 = Vial4ml::get_or_create(&test_user, &mut conn).expect("Failed to create vial model");
+///     assert_eq!(vial1, vial2);
+///     assert_eq!(Vial4ml::NAME, VIAL_4ML.name);
+///     assert!(find_vial_4ml_in("lab_reference", &mut conn).expect("Failed to look up the vial 4ml model").is_none());
+/// }
+/// ```
+#[macro_export]
+macro_rules! reference_template {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $function:ident {
+            table: $table:ident,
+            name: $name:literal,
+            description: $description:literal
+            $(, $attribute:ident: $value:expr)*
+            $(,)?
+        }
+        $(
+            test {
+                conn: $conn:expr,
+                user: $user:expr
+                $(,)?
+            }
+        )?
+    ) => {
+        $crate::__private::paste! {
+            #[doc = concat!("Definition of the `", $name, "` model.")]
+            $vis const [<$function:upper>]: $crate::definitions::TemplateDefinition =
+                $crate::definitions::TemplateDefinition::new(
+                    $crate::definitions::ModelTable::[<$table:camel>],
+                    $name,
+                    $description,
+                )
+                $(.$attribute($value))*;
+
+            $(#[$meta])*
+            $vis fn $function<'a, C>(
+                context: impl ::core::convert::Into<$crate::context::TemplateContext<'a>>,
+                conn: &mut C,
+            ) -> ::core::result::Result<
+                $crate::__private::NestedModel<$crate::__private::aps::[<aps_ $table>]::$table::table>,
+                $crate::errors::TemplateError,
+            >
+            where
+                C: $crate::__private::diesel::Connection,
+                $crate::__private::TableBuilder<
+                    $crate::__private::aps::[<aps_ $table>]::$table::table,
+                >: $crate::__private::Insert<C>,
+                $crate::__private::TableBuilder<
+                    $crate::__private::aps::aps_namespaces::namespaces::table,
+                >: $crate::__private::Insert<C>,
+                ($crate::__private::aps::aps_namespaces::namespaces::name,):
+                    $crate::__private::LoadNestedFirst<
+                        $crate::__private::aps::aps_namespaces::namespaces::table,
+                        C,
+                    >,
+                (
+                    $crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::namespace_id,
+                    ($crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::name,),
+                ): $crate::__private::LoadNestedFirst<
+                    $crate::__private::aps::[<aps_ $table>]::$table::table,
+                    C,
+                >,
+            {
+                use $crate::__private::WithContextId;
+                use $crate::__private::aps::[<aps_ $table>]::*;
+                use $crate::__private::aps::aps_namespaced_ownables::*;
+                use $crate::__private::aps::aps_namespaces::*;
+                use $crate::__private::aps::aps_ownables::*;
+                use $crate::__private::aps::aps_users::*;
+                use $crate::__private::diesel_builders::prelude::*;
+                use $crate::errors::TemplateError;
+
+                let context = context.into();
+                let reference_namespace =
+                    $crate::reference_namespaces::reference_namespace(context, conn)?;
+                $crate::__private::get_or_insert(
+                    |conn| {
+                        $crate::__private::load_active_reference_model(
+                            &context,
+                            &reference_namespace,
+                            $name,
+                            conn,
+                        )
+                    },
+                    |conn| {
+                        $table::table::builder()
+                            .try_name($name)
+                            .map_err(TemplateError::attribute($name, "name"))?
+                            .try_description($description)
+                            .map_err(TemplateError::attribute($name, "description"))?
+                            $(
+                                .[<try_ $attribute>]($value)
+                                .map_err(TemplateError::attribute($name, stringify!($attribute)))?
+                            )*
+                            .creator_id(context.creator.get_column::<users::id>())
+                            .editor_id(context.editor.get_column::<users::id>())
+                            .owner_id(context.owner.get_column::<users::id>())
+                            .namespace_id(reference_namespace.get_column::<namespaces::id>())
+                            .template_id(&context, $name)
+                            .insert_nested(conn)
+                            .map_err(TemplateError::insert($name))
+                    },
+                    conn,
+                )
+            }
+
+            #[doc = concat!("Returns the `", $name, "` model, if it exists.")]
+            ///
+            /// Shorthand for `AssetModelTemplate::find` on the zero-sized type
+            /// of the template.
+            ///
+            /// # Errors
+            ///
+            /// * If the lookup fails for any reason other than the model not
+            ///   existing.
+            $vis fn [<find_ $function>]<C>(
+                conn: &mut C,
+            ) -> ::core::result::Result<
+                ::core::option::Option<
+                    $crate::__private::NestedModel<$crate::__private::aps::[<aps_ $table>]::$table::table>,
+                >,
+                $crate::errors::TemplateError,
+            >
+            where
+                ($crate::__private::aps::aps_namespaces::namespaces::name,):
+                    $crate::__private::LoadNestedFirst<
+                        $crate::__private::aps::aps_namespaces::namespaces::table,
+                        C,
+                    >,
+                (
+                    $crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::namespace_id,
+                    ($crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::name,),
+                ): $crate::__private::LoadNestedFirst<
+                    $crate::__private::aps::[<aps_ $table>]::$table::table,
+                    C,
+                >,
+            {
+                <[<$function:camel>] as $crate::template::AssetModelTemplate>::find(conn)
+            }
+
+            #[doc = concat!("Returns the `", $name, "` model from the provided namespace, if it exists.")]
+            ///
+            /// Shorthand for `AssetModelTemplate::find_in` on the zero-sized
+            /// type of the template.
+            ///
+            /// # Errors
+            ///
+            /// * If the lookup fails for any reason other than the model not
+            ///   existing.
+            $vis fn [<find_ $function _in>]<C>(
+                namespace_name: &str,
+                conn: &mut C,
+            ) -> ::core::result::Result<
+                ::core::option::Option<
+                    $crate::__private::NestedModel<$crate::__private::aps::[<aps_ $table>]::$table::table>,
+                >,
+                $crate::errors::TemplateError,
+            >
+            where
+                ($crate::__private::aps::aps_namespaces::namespaces::name,):
+                    $crate::__private::LoadNestedFirst<
+                        $crate::__private::aps::aps_namespaces::namespaces::table,
+                        C,
+                    >,
+                (
+                    $crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::namespace_id,
+                    ($crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::name,),
+                ): $crate::__private::LoadNestedFirst<
+                    $crate::__private::aps::[<aps_ $table>]::$table::table,
+                    C,
+                >,
+            {
+                <[<$function:camel>] as $crate::template::AssetModelTemplate>::find_in(
+                    namespace_name,
+                    conn,
+                )
+            }
+
+            #[doc = concat!("Reference template of the `", $name, "` model.")]
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+            $vis struct [<$function:camel>];
+
+            impl $crate::template::AssetModelTemplate for [<$function:camel>] {
+                type Table = $crate::__private::aps::[<aps_ $table>]::$table::table;
+
+                const NAME: &'static str = $name;
+                const DESCRIPTION: &'static str = $description;
+
+                fn get_or_create<'a, C>(
+                    context: impl ::core::convert::Into<$crate::context::TemplateContext<'a>>,
+                    conn: &mut C,
+                ) -> ::core::result::Result<
+                    $crate::__private::NestedModel<Self::Table>,
+                    $crate::errors::TemplateError,
+                >
+                where
+                    C: $crate::__private::diesel::Connection,
+                    $crate::__private::TableBuilder<Self::Table>: $crate::__private::Insert<C>,
+                    $crate::__private::TableBuilder<
+                        $crate::__private::aps::aps_namespaces::namespaces::table,
+                    >: $crate::__private::Insert<C>,
+                    ($crate::__private::aps::aps_namespaces::namespaces::name,):
+                        $crate::__private::LoadNestedFirst<
+                            $crate::__private::aps::aps_namespaces::namespaces::table,
+                            C,
+                        >,
+                    (
+                        $crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::namespace_id,
+                        ($crate::__private::aps::aps_namespaced_ownables::namespaced_ownables::name,),
+                    ): $crate::__private::LoadNestedFirst<Self::Table, C>,
+                {
+                    $function(context, conn)
+                }
+            }
+
+            $(
+                #[cfg(test)]
+                #[test]
+                fn [<$function _is_idempotent>]() {
+                    let mut conn = $conn;
+                    let test_user = ($user)(&mut conn);
+                    let model1 = $function(&test_user, &mut conn)
+                        .expect(concat!("Failed to create the `", $name, "` model"));
+                    let model2 = $function(&test_user, &mut conn)
+                        .expect(concat!("Failed to create the `", $name, "` model"));
+                    assert_eq!(model1, model2);
+                    assert_eq!(
+                        [<find_ $function>](&mut conn)
+                            .expect(concat!("Failed to look up the `", $name, "` model")),
+                        Some(model1)
+                    );
+                }
+            )?
+        }
+    };
+}
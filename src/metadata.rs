@@ -0,0 +1,187 @@
+//! Submodule storing metadata about the reference catalog of each namespace,
+//! such as the version of the catalog which seeded it.
+//!
+//! The metadata lives in the `aps_templates_metadata` table, which is not part
+//! of the APS schema. It is created once, by
+//! [`apply_template_migrations`](crate::migrations::apply_template_migrations)
+//! which every seeding runs first, and reads treat a missing table as empty,
+//! so that looking up metadata never runs DDL.
+
+use crate::errors::TemplateError;
+use diesel::query_builder::SqlQuery;
+use diesel::query_dsl::methods::{ExecuteDsl, LoadQuery};
+use diesel::{
+    Connection, ExpressionMethods, OptionalExtension, QueryDsl, QueryableByName, RunQueryDsl, dsl,
+};
+
+diesel::table! {
+    /// Key-value metadata about the reference catalog of each namespace.
+    aps_templates_metadata (namespace_name, key) {
+        /// Name of the namespace the entry describes.
+        namespace_name -> Text,
+        /// Key of the entry, e.g. `catalog_version`.
+        key -> Text,
+        /// Value of the entry.
+        value -> Text,
+    }
+}
+
+/// Statement creating the metadata table if it does not exist yet.
+pub(crate) const CREATE_METADATA_TABLE: &str = "CREATE TABLE IF NOT EXISTS aps_templates_metadata (\
+     namespace_name TEXT NOT NULL, \
+     key TEXT NOT NULL, \
+     value TEXT NOT NULL, \
+     PRIMARY KEY (namespace_name, key))";
+
+/// Query telling whether the metadata table exists.
+const METADATA_TABLE_EXISTS: &str =
+    "SELECT to_regclass('aps_templates_metadata') IS NOT NULL AS present";
+
+/// Primary key of an entry of the metadata table.
+type MetadataKey = (String, String);
+
+/// Row returned by the query checking whether the metadata table exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, QueryableByName)]
+pub struct MetadataTable {
+    /// Whether the metadata table exists.
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    pub present: bool,
+}
+
+/// Connection able to read and write the metadata of the reference catalog.
+///
+/// This trait is implemented for every connection satisfying the bounds of
+/// the queries on the metadata table, and only exists so that the functions
+/// using the metadata do not need to repeat them.
+pub trait CatalogMetadata: Connection {
+    /// Returns whether the metadata table exists.
+    ///
+    /// # Errors
+    ///
+    /// * If the database catalog cannot be queried.
+    fn has_metadata_table(&mut self) -> Result<bool, TemplateError>;
+
+    /// Creates the metadata table if it does not exist yet.
+    ///
+    /// The creation runs in a savepoint, and when it fails because a
+    /// concurrent caller created the table first, the failure is ignored.
+    ///
+    /// # Errors
+    ///
+    /// * If the metadata table cannot be created.
+    fn create_metadata_table(&mut self) -> Result<(), TemplateError>;
+
+    /// Returns the value of the provided key for the provided namespace, if
+    /// it was ever written.
+    ///
+    /// A missing metadata table is read as an empty one.
+    ///
+    /// # Errors
+    ///
+    /// * If the metadata table exists but cannot be read.
+    fn read_metadata(
+        &mut self,
+        namespace_name: &str,
+        key: &str,
+    ) -> Result<Option<String>, TemplateError>;
+
+    /// Sets the value of the provided key for the provided namespace,
+    /// overwriting any previous value.
+    ///
+    /// The write runs in a savepoint, and when it fails because a concurrent
+    /// caller wrote the same value first, the failure is ignored.
+    ///
+    /// # Errors
+    ///
+    /// * If the metadata table does not exist, see
+    ///   [`create_metadata_table`](Self::create_metadata_table).
+    /// * If the metadata table cannot be written.
+    fn write_metadata(
+        &mut self,
+        namespace_name: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), TemplateError>;
+}
+
+impl<C> CatalogMetadata for C
+where
+    C: Connection,
+    SqlQuery: ExecuteDsl<C> + LoadQuery<'static, C, MetadataTable>,
+    dsl::Select<
+        dsl::Find<aps_templates_metadata::table, MetadataKey>,
+        aps_templates_metadata::value,
+    >: LoadQuery<'static, C, String>,
+    dsl::Delete<dsl::Find<aps_templates_metadata::table, MetadataKey>>: ExecuteDsl<C>,
+    dsl::Values<
+        dsl::InsertInto<aps_templates_metadata::table>,
+        (
+            dsl::Eq<aps_templates_metadata::namespace_name, String>,
+            dsl::Eq<aps_templates_metadata::key, String>,
+            dsl::Eq<aps_templates_metadata::value, String>,
+        ),
+    >: ExecuteDsl<C>,
+{
+    fn has_metadata_table(&mut self) -> Result<bool, TemplateError> {
+        Ok(diesel::sql_query(METADATA_TABLE_EXISTS)
+            .get_result::<MetadataTable>(self)?
+            .present)
+    }
+
+    fn create_metadata_table(&mut self) -> Result<(), TemplateError> {
+        if self.has_metadata_table()? {
+            return Ok(());
+        }
+        let created = self.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::sql_query(CREATE_METADATA_TABLE).execute(conn)?;
+            Ok(())
+        });
+        match created {
+            Ok(()) => Ok(()),
+            Err(_) if self.has_metadata_table()? => Ok(()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn read_metadata(
+        &mut self,
+        namespace_name: &str,
+        key: &str,
+    ) -> Result<Option<String>, TemplateError> {
+        if !self.has_metadata_table()? {
+            return Ok(None);
+        }
+        Ok(aps_templates_metadata::table
+            .find((namespace_name.to_owned(), key.to_owned()))
+            .select(aps_templates_metadata::value)
+            .get_result(self)
+            .optional()?)
+    }
+
+    fn write_metadata(
+        &mut self,
+        namespace_name: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), TemplateError> {
+        let written = self.transaction(|conn| {
+            diesel::delete(
+                aps_templates_metadata::table.find((namespace_name.to_owned(), key.to_owned())),
+            )
+            .execute(conn)?;
+            diesel::insert_into(aps_templates_metadata::table)
+                .values((
+                    aps_templates_metadata::namespace_name.eq(namespace_name.to_owned()),
+                    aps_templates_metadata::key.eq(key.to_owned()),
+                    aps_templates_metadata::value.eq(value.to_owned()),
+                ))
+                .execute(conn)?;
+            Ok(())
+        });
+        match written {
+            Ok(()) => Ok(()),
+            Err(_) if self.read_metadata(namespace_name, key)?.as_deref() == Some(value) => Ok(()),
+            Err(error) => Err(error),
+        }
+    }
+}
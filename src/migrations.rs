@@ -0,0 +1,451 @@
+//! Submodule migrating the stored reference models when their templates are
+//! renamed, redescribed or moved to another table.
+//!
+//! Templates are looked up by name, so renaming one in the code alone would
+//! make the next seeding create a second model and leave every asset pointing
+//! to the old one. The [`TEMPLATE_MIGRATIONS`] instead update the stored
+//! models in place, keeping their identifiers. Each migration is applied once
+//! per namespace, which is recorded in the
+//! [`aps_templates_metadata`](crate::metadata::aps_templates_metadata) table,
+//! and the pending ones are applied by
+//! [`seed_reference_catalog`](crate::catalog::seed_reference_catalog) before
+//! any model is created.
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::definitions::{ModelTable, ReagentProperties, TemplateDefinition};
+use crate::errors::TemplateError;
+use crate::lookup::ReferenceReader;
+use crate::metadata::CatalogMetadata;
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+use crate::version::CatalogVersion;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_reagent_models::*;
+use diesel::query_dsl::methods::ExecuteDsl;
+use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl, dsl};
+use diesel_builders::prelude::*;
+use rosetta_uuid::Uuid;
+
+/// Step of a [`TemplateMigration`].
+///
+/// Every step is a no-op when the model it migrates does not exist, as in a
+/// namespace seeded after the step was introduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStep {
+    /// Renames a model, keeping its identifier.
+    ///
+    /// The step fails if a model with the new name already exists.
+    Rename {
+        /// Table in which the model is stored.
+        table: ModelTable,
+        /// Former name of the model.
+        from: &'static str,
+        /// New name of the model.
+        to: &'static str,
+    },
+    /// Overwrites the description of a model, e.g. when it changes along with
+    /// a rename, so that the renamed model does not show up as drifted.
+    Describe {
+        /// Table in which the model is stored.
+        table: ModelTable,
+        /// Name of the model.
+        name: &'static str,
+        /// New description of the model.
+        description: &'static str,
+    },
+    /// Moves a model to a table extending the one it is stored in, keeping its
+    /// identifier, by inserting its row in the extending table with the
+    /// attributes of its current definition.
+    ///
+    /// Only the [`ModelTable::ReagentModels`] are supported as target, and the
+    /// step is a no-op when the model is already stored in the target table.
+    Extend {
+        /// Table in which the model is stored.
+        from: ModelTable,
+        /// Table extending the former one in which to store the model.
+        to: ModelTable,
+        /// Name of the model, which must be one of the
+        /// [`REFERENCE_TEMPLATES`](crate::asset_models::REFERENCE_TEMPLATES).
+        name: &'static str,
+    },
+}
+
+/// Ordered steps migrating the stored reference models, applied once per
+/// namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateMigration {
+    /// Identifier of the migration, never to be changed once released.
+    pub id: &'static str,
+    /// Steps of the migration, applied in order.
+    pub steps: &'static [MigrationStep],
+}
+
+/// Every migration of the reference models, in the order they are applied.
+///
+/// New migrations are appended to the end of the list, and released ones are
+/// never edited nor removed.
+pub const TEMPLATE_MIGRATIONS: &[TemplateMigration] = &[
+    TemplateMigration {
+        id: "0001_reagent_models",
+        steps: &[
+            reagent_step("Distilled water"),
+            reagent_step("Absolute Ethanol, >= 95%"),
+            reagent_step("Formic acid"),
+            reagent_step("Liquid nitrogen"),
+            reagent_step("Methanol, >= 99.8%, HPLC grade"),
+        ],
+    },
+    TemplateMigration {
+        id: "0002_split_cap_vial_1_5ml",
+        steps: &[
+            MigrationStep::Rename {
+                table: ModelTable::ContainerSealerModels,
+                from: "Splitted Cap for Vial 1.5ml",
+                to: "Split Cap for Vial 1.5ml",
+            },
+            MigrationStep::Describe {
+                table: ModelTable::ContainerSealerModels,
+                name: "Split Cap for Vial 1.5ml",
+                description: "Split cap for Vial of 1.5 ml used for extracts storage",
+            },
+        ],
+    },
+];
+
+/// Returns the step moving the reagent with the provided name from the
+/// physical asset models, where reagents were stored before having their own
+/// table, to the reagent models.
+const fn reagent_step(name: &'static str) -> MigrationStep {
+    MigrationStep::Extend {
+        from: ModelTable::PhysicalAssetModels,
+        to: ModelTable::ReagentModels,
+        name,
+    }
+}
+
+/// Returns the name under which the template with the provided name was
+/// first released, following back the renames of the
+/// [`TEMPLATE_MIGRATIONS`].
+///
+/// The identifiers and IRIs of the templates are derived from this name, so
+/// that they survive renames.
+pub(crate) fn original_template_name(template_name: &str) -> &str {
+    TEMPLATE_MIGRATIONS
+        .iter()
+        .rev()
+        .flat_map(|migration| migration.steps.iter().rev())
+        .fold(template_name, |name, step| match *step {
+            MigrationStep::Rename { from, to, .. } if to == name => from,
+            _ => name,
+        })
+}
+
+/// Returns the table and name under which the model of the provided template
+/// is stored before the provided migrations are applied, following back
+/// their renames and moves to an extending table.
+pub(crate) fn unmigrated_model(
+    migrations: &[&TemplateMigration],
+    definition: &TemplateDefinition,
+) -> (ModelTable, &'static str) {
+    migrations
+        .iter()
+        .rev()
+        .flat_map(|migration| migration.steps.iter().rev())
+        .fold(
+            (definition.table, definition.name),
+            |(table, name), step| match *step {
+                MigrationStep::Rename {
+                    table: renamed,
+                    from,
+                    to,
+                } if renamed == table && to == name => (table, from),
+                MigrationStep::Extend {
+                    from,
+                    to,
+                    name: extended,
+                } if to == table && extended == name => (from, name),
+                _ => (table, name),
+            },
+        )
+}
+
+/// Prefix of the metadata keys recording the applied migrations.
+pub(crate) const MIGRATION_KEY_PREFIX: &str = "migration:";
+
+/// Returns the [`TEMPLATE_MIGRATIONS`] not yet applied to the provided
+/// namespace.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read.
+pub(crate) fn pending_migrations<C: CatalogMetadata>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Vec<&'static TemplateMigration>, TemplateError> {
+    let mut pending = Vec::new();
+    for migration in TEMPLATE_MIGRATIONS {
+        let key = format!("{MIGRATION_KEY_PREFIX}{}", migration.id);
+        if conn.read_metadata(namespace_name, &key)?.is_none() {
+            pending.push(migration);
+        }
+    }
+    Ok(pending)
+}
+
+/// Connection able to apply the [`TEMPLATE_MIGRATIONS`].
+///
+/// This trait is implemented for every connection satisfying the update
+/// bounds of the migration steps, and only exists so that the functions
+/// applying them do not need to repeat them.
+pub trait MigrationWriter: ReferenceReader + CatalogMetadata {
+    /// Renames the model with the provided identifier.
+    ///
+    /// # Errors
+    ///
+    /// * If the update fails.
+    fn rename_model(&mut self, id: Uuid, name: &'static str) -> Result<(), TemplateError>;
+
+    /// Overwrites the description of the model with the provided identifier.
+    ///
+    /// # Errors
+    ///
+    /// * If the update fails.
+    fn describe_model(
+        &mut self,
+        id: Uuid,
+        name: &'static str,
+        description: &'static str,
+    ) -> Result<(), TemplateError>;
+
+    /// Inserts the reagent row of the physical asset model with the provided
+    /// identifier, stored in the namespace with the provided identifier,
+    /// making it a reagent model.
+    ///
+    /// # Errors
+    ///
+    /// * If the insertion fails, e.g. when another reagent model of the
+    ///   namespace has the same CAS code.
+    fn insert_reagent_row(
+        &mut self,
+        id: Uuid,
+        namespace_id: Uuid,
+        name: &'static str,
+        properties: ReagentProperties<'static>,
+    ) -> Result<(), TemplateError>;
+}
+
+impl<C> MigrationWriter for C
+where
+    C: ReferenceReader + CatalogMetadata,
+    dsl::Update<
+        dsl::Find<namespaced_ownables::table, Uuid>,
+        dsl::Eq<namespaced_ownables::name, &'static str>,
+    >: ExecuteDsl<C>,
+    dsl::Update<
+        dsl::Find<namespaced_ownables::table, Uuid>,
+        dsl::Eq<namespaced_ownables::description, &'static str>,
+    >: ExecuteDsl<C>,
+    dsl::Values<
+        dsl::InsertInto<reagent_models::table>,
+        (
+            dsl::Eq<reagent_models::id, Uuid>,
+            dsl::Eq<reagent_models::namespace_id, Uuid>,
+            dsl::Eq<reagent_models::purity, f32>,
+            dsl::Eq<reagent_models::cas_code, &'static str>,
+            dsl::Eq<reagent_models::molecular_formula, &'static str>,
+        ),
+    >: ExecuteDsl<C>,
+{
+    fn rename_model(&mut self, id: Uuid, name: &'static str) -> Result<(), TemplateError> {
+        diesel::update(namespaced_ownables::table.find(id))
+            .set(namespaced_ownables::name.eq(name))
+            .execute(self)
+            .map_err(TemplateError::update(name))?;
+        Ok(())
+    }
+
+    fn describe_model(
+        &mut self,
+        id: Uuid,
+        name: &'static str,
+        description: &'static str,
+    ) -> Result<(), TemplateError> {
+        diesel::update(namespaced_ownables::table.find(id))
+            .set(namespaced_ownables::description.eq(description))
+            .execute(self)
+            .map_err(TemplateError::update(name))?;
+        Ok(())
+    }
+
+    fn insert_reagent_row(
+        &mut self,
+        id: Uuid,
+        namespace_id: Uuid,
+        name: &'static str,
+        properties: ReagentProperties<'static>,
+    ) -> Result<(), TemplateError> {
+        diesel::insert_into(reagent_models::table)
+            .values((
+                reagent_models::id.eq(id),
+                reagent_models::namespace_id.eq(namespace_id),
+                reagent_models::purity.eq(properties.purity),
+                reagent_models::cas_code.eq(properties.cas_code),
+                reagent_models::molecular_formula.eq(properties.molecular_formula),
+            ))
+            .execute(self)
+            .map_err(TemplateError::update(name))?;
+        Ok(())
+    }
+}
+
+/// Returns the identifier of the model with the provided name in the provided
+/// table of the provided namespace, if it exists.
+fn model_id<C: ReferenceReader>(
+    namespace: &NestedModel<namespaces::table>,
+    table: ModelTable,
+    name: &'static str,
+    conn: &mut C,
+) -> Result<Option<Uuid>, TemplateError> {
+    Ok(conn
+        .find_stored_model(namespace, table, name)?
+        .map(|stored| stored.id))
+}
+
+/// Applies the provided step to the provided namespace.
+fn apply_step<C: MigrationWriter>(
+    migration: &TemplateMigration,
+    namespace: &NestedModel<namespaces::table>,
+    step: MigrationStep,
+    conn: &mut C,
+) -> Result<(), TemplateError> {
+    match step {
+        MigrationStep::Rename { table, from, to } => {
+            let Some(from_id) = model_id(namespace, table, from, conn)? else {
+                return Ok(());
+            };
+            if model_id(namespace, table, to, conn)?.is_some() {
+                return Err(TemplateError::Migration {
+                    migration: migration.id,
+                    reason: format!("cannot rename `{from}` to `{to}`, which already exists"),
+                });
+            }
+            conn.rename_model(from_id, to)
+        }
+        MigrationStep::Describe {
+            table,
+            name,
+            description,
+        } => match model_id(namespace, table, name, conn)? {
+            Some(id) => conn.describe_model(id, name, description),
+            None => Ok(()),
+        },
+        MigrationStep::Extend { from, to, name } => {
+            if model_id(namespace, to, name, conn)?.is_some() {
+                return Ok(());
+            }
+            let Some(id) = model_id(namespace, from, name, conn)? else {
+                return Ok(());
+            };
+            let properties = REFERENCE_TEMPLATES
+                .iter()
+                .find(|definition| definition.name == name && definition.table == to)
+                .and_then(|definition| definition.reagent)
+                .ok_or_else(|| TemplateError::Migration {
+                    migration: migration.id,
+                    reason: format!("`{name}` is not a reference reagent template"),
+                })?;
+            let namespace_id = namespace.get_column::<namespaces::id>().to_owned();
+            conn.insert_reagent_row(id, namespace_id, name, properties)
+        }
+    }
+}
+
+/// Applies the [`TEMPLATE_MIGRATIONS`] not yet applied to the default
+/// reference namespace, returning the identifiers of the applied ones.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups or updates fails, in which case the failing
+///   migration and the following ones are not applied.
+/// * If a step cannot be applied, e.g. when renaming a model to the name of
+///   another one.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel::RunQueryDsl;
+/// let mut conn = aps_git_conn();
+///
+/// let applied = apply_template_migrations(&mut conn).expect("Failed to apply the migrations");
+/// assert_eq!(applied.len(), TEMPLATE_MIGRATIONS.len());
+/// assert!(apply_template_migrations(&mut conn).expect("Failed to apply the migrations").is_empty());
+///
+/// let test_user = user(&mut conn);
+/// let split_cap = split_cap_vial_1_5ml(&test_user, &mut conn).expect("Failed to create split cap model");
+/// diesel::sql_query("UPDATE namespaced_ownables SET name = 'Splitted Cap for Vial 1.5ml' WHERE name = 'Split Cap for Vial 1.5ml'")
+///     .execute(&mut conn)
+///     .expect("Failed to restore the former name");
+/// diesel::sql_query("DELETE FROM aps_templates_metadata WHERE key LIKE 'migration:%'")
+///     .execute(&mut conn)
+///     .expect("Failed to forget the applied migrations");
+///
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// assert_eq!(find_split_cap_vial_1_5ml(&mut conn).expect("Failed to look up the split cap"), Some(split_cap));
+/// ```
+pub fn apply_template_migrations<C: MigrationWriter>(
+    conn: &mut C,
+) -> Result<Vec<&'static str>, TemplateError> {
+    apply_template_migrations_in(REFERENCE_NAMESPACE_NAME, conn)
+}
+
+/// Applies the [`TEMPLATE_MIGRATIONS`] not yet applied to the provided
+/// namespace, returning the identifiers of the applied ones.
+///
+/// The [`aps_templates_metadata`](crate::metadata::aps_templates_metadata)
+/// table is created first if missing. Each migration is then applied within
+/// a transaction, together with the record of its application.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If the metadata table cannot be created.
+/// * If any of the lookups or updates fails, in which case the failing
+///   migration and the following ones are not applied.
+/// * If a step cannot be applied, e.g. when renaming a model to the name of
+///   another one.
+pub fn apply_template_migrations_in<C: MigrationWriter>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Vec<&'static str>, TemplateError> {
+    conn.create_metadata_table()?;
+    let mut applied = Vec::new();
+    for migration in TEMPLATE_MIGRATIONS {
+        let key = format!("{MIGRATION_KEY_PREFIX}{}", migration.id);
+        if conn.read_metadata(namespace_name, &key)?.is_some() {
+            continue;
+        }
+        conn.transaction(|conn| {
+            if let Some(namespace) = conn.find_namespace(namespace_name)? {
+                for step in migration.steps {
+                    apply_step(migration, &namespace, *step, conn)?;
+                }
+            }
+            conn.write_metadata(namespace_name, &key, &CatalogVersion::current().to_string())
+        })?;
+        applied.push(migration.id);
+    }
+    Ok(applied)
+}
@@ -0,0 +1,222 @@
+//! Submodule planning which reference models a seeding would create, without
+//! writing anything.
+//!
+//! Seeding applies the pending
+//! [`TEMPLATE_MIGRATIONS`](crate::migrations::TEMPLATE_MIGRATIONS) first, so
+//! the plan looks up the models of the templates renamed or moved by a pending
+//! migration under their former name and table too.
+
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::errors::TemplateError;
+use crate::lookup::ReferenceReader;
+use crate::metadata::CatalogMetadata;
+use crate::migrations::{pending_migrations, unmigrated_model};
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+
+/// What seeding the catalog would do with a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedAction {
+    /// The row already exists and would be reused.
+    Exists,
+    /// The row does not exist and would be created.
+    Create,
+}
+
+impl Display for PlannedAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exists => write!(f, "exists"),
+            Self::Create => write!(f, "create"),
+        }
+    }
+}
+
+/// Planned outcome of a single reference template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlannedTemplate {
+    /// Name of the template.
+    pub template: &'static str,
+    /// Name of the table storing the model of the template.
+    pub table: &'static str,
+    /// What seeding would do with the model.
+    pub action: PlannedAction,
+    /// Names of the templates whose creation would pull this one in as a
+    /// parent or as a sample source.
+    pub pulled_in_by: Vec<&'static str>,
+}
+
+/// Planned outcome of seeding the whole reference catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReferencePlan {
+    /// Name of the reference namespace.
+    pub namespace_name: String,
+    /// What seeding would do with the reference namespace.
+    pub namespace: PlannedAction,
+    /// Planned outcome of every reference template, parents included.
+    pub templates: Vec<PlannedTemplate>,
+}
+
+impl ReferencePlan {
+    /// Returns the templates whose model would be created.
+    pub fn to_create(&self) -> impl Iterator<Item = &PlannedTemplate> {
+        self.templates
+            .iter()
+            .filter(|planned| planned.action == PlannedAction::Create)
+    }
+
+    /// Returns whether seeding would not write anything.
+    #[must_use]
+    pub fn is_noop(&self) -> bool {
+        self.namespace == PlannedAction::Exists && self.to_create().next().is_none()
+    }
+}
+
+impl Display for ReferencePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} namespace `{}`", self.namespace, self.namespace_name)?;
+        for planned in &self.templates {
+            write!(
+                f,
+                "{} `{}` in `{}`",
+                planned.action, planned.template, planned.table
+            )?;
+            if !planned.pulled_in_by.is_empty() {
+                write!(f, ", pulled in by `{}`", planned.pulled_in_by.join("`, `"))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns which reference models seeding the catalog in the default
+/// reference namespace would reuse and which it would create.
+///
+/// Nothing is written to the database. A model which a pending template
+/// migration would rename or move is planned as reused.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+/// * If the applied migrations cannot be read.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel::RunQueryDsl;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// let plan = plan_reference_catalog(&mut conn).expect("Failed to plan the reference catalog");
+/// assert_eq!(plan.namespace, PlannedAction::Create);
+/// assert_eq!(plan.to_create().count(), REFERENCE_TEMPLATES.len());
+/// let standard_rack = plan.templates.iter().find(|planned| planned.template == STANDARD_RACK.name).unwrap();
+/// assert_eq!(standard_rack.pulled_in_by, vec![CONICAL_CENTRIFUGAL_TUBE_50ML_RACK.name]);
+/// println!("{plan}");
+///
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// let plan = plan_reference_catalog(&mut conn).expect("Failed to plan the reference catalog");
+/// assert!(plan.is_noop());
+///
+/// diesel::sql_query("UPDATE namespaced_ownables SET name = 'Splitted Cap for Vial 1.5ml' WHERE name = 'Split Cap for Vial 1.5ml'")
+///     .execute(&mut conn)
+///     .expect("Failed to restore the former name");
+/// diesel::sql_query("DELETE FROM aps_templates_metadata WHERE key LIKE 'migration:%'")
+///     .execute(&mut conn)
+///     .expect("Failed to forget the applied migrations");
+/// let plan = plan_reference_catalog(&mut conn).expect("Failed to plan the reference catalog");
+/// assert!(plan.is_noop());
+/// ```
+pub fn plan_reference_catalog<C: ReferenceReader + CatalogMetadata>(
+    conn: &mut C,
+) -> Result<ReferencePlan, TemplateError> {
+    plan_reference_catalog_in(REFERENCE_NAMESPACE_NAME, conn)
+}
+
+/// Returns which reference models seeding the catalog in the provided
+/// namespace would reuse and which it would create.
+///
+/// Nothing is written to the database. A model which a pending template
+/// migration would rename or move is planned as reused.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+/// * If the applied migrations cannot be read.
+pub fn plan_reference_catalog_in<C: ReferenceReader + CatalogMetadata>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<ReferencePlan, TemplateError> {
+    let namespace = conn.find_namespace(namespace_name)?;
+    let pending = pending_migrations(namespace_name, conn)?;
+
+    let mut templates = Vec::with_capacity(REFERENCE_TEMPLATES.len());
+    for definition in REFERENCE_TEMPLATES {
+        let exists = match &namespace {
+            Some(namespace) => {
+                let (table, name) = unmigrated_model(&pending, definition);
+                conn.find_stored_model(namespace, definition.table, definition.name)?
+                    .is_some()
+                    || (name != definition.name || table != definition.table)
+                        && conn.find_stored_model(namespace, table, name)?.is_some()
+            }
+            None => false,
+        };
+        templates.push(PlannedTemplate {
+            template: definition.name,
+            table: definition.table.table_name(),
+            action: if exists {
+                PlannedAction::Exists
+            } else {
+                PlannedAction::Create
+            },
+            pulled_in_by: Vec::new(),
+        });
+    }
+
+    for (index, definition) in REFERENCE_TEMPLATES.iter().enumerate() {
+        if templates[index].action == PlannedAction::Exists {
+            continue;
+        }
+        let mut dependencies: Vec<_> = definition.dependencies().collect();
+        while let Some(dependency) = dependencies.pop() {
+            if let Some(planned) = templates
+                .iter_mut()
+                .find(|planned| planned.template == dependency.name)
+                .filter(|planned| planned.action == PlannedAction::Create)
+            {
+                planned.pulled_in_by.push(definition.name);
+            }
+            dependencies.extend(dependency.dependencies());
+        }
+    }
+
+    Ok(ReferencePlan {
+        namespace_name: namespace_name.to_owned(),
+        namespace: if namespace.is_some() {
+            PlannedAction::Exists
+        } else {
+            PlannedAction::Create
+        },
+        templates,
+    })
+}
@@ -0,0 +1,195 @@
+//! Submodule updating the stored reference models which drifted from their
+//! definitions.
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::context::TemplateContext;
+use crate::definitions::TemplateDefinition;
+use crate::drift::{TemplateDrift, field_drifts};
+use crate::errors::TemplateError;
+use crate::lookup::ReferenceReader;
+use crate::metadata::CatalogMetadata;
+use crate::version::record_catalog_version_in;
+use aps::aps_bead_models::*;
+use aps::aps_digital_asset_models::*;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_ownables::*;
+use aps::aps_reagent_models::*;
+use aps::aps_users::*;
+use aps::aps_volumetric_container_models::*;
+use diesel::query_dsl::methods::ExecuteDsl;
+use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl, dsl};
+use diesel_builders::prelude::*;
+use rosetta_uuid::Uuid;
+
+/// Connection able to update the models of every reference template.
+///
+/// This trait is implemented for every connection satisfying the update
+/// bounds of all the reference models, and only exists so that the functions
+/// working across the whole catalog do not need to repeat them.
+pub trait ReferenceWriter: ReferenceReader + Connection {
+    /// Overwrites the attributes of the model with the provided identifier with
+    /// the ones of the provided definition, recording the provided user as its
+    /// last editor.
+    ///
+    /// # Errors
+    ///
+    /// * If any of the updates fails.
+    fn update_stored_model(
+        &mut self,
+        id: Uuid,
+        definition: &TemplateDefinition,
+        editor_id: Uuid,
+    ) -> Result<(), TemplateError>;
+}
+
+impl<C> ReferenceWriter for C
+where
+    C: ReferenceReader + Connection,
+    dsl::Update<
+        dsl::Find<namespaced_ownables::table, Uuid>,
+        dsl::Eq<namespaced_ownables::description, &'static str>,
+    >: ExecuteDsl<C>,
+    dsl::Update<
+        dsl::Find<volumetric_container_models::table, Uuid>,
+        dsl::Eq<volumetric_container_models::volume, f32>,
+    >: ExecuteDsl<C>,
+    dsl::Update<dsl::Find<bead_models::table, Uuid>, dsl::Eq<bead_models::diameter, f32>>:
+        ExecuteDsl<C>,
+    dsl::Update<
+        dsl::Find<digital_asset_models::table, Uuid>,
+        dsl::Eq<digital_asset_models::mime_type, &'static str>,
+    >: ExecuteDsl<C>,
+    dsl::Update<
+        dsl::Find<reagent_models::table, Uuid>,
+        (
+            dsl::Eq<reagent_models::purity, f32>,
+            dsl::Eq<reagent_models::cas_code, &'static str>,
+            dsl::Eq<reagent_models::molecular_formula, &'static str>,
+        ),
+    >: ExecuteDsl<C>,
+    dsl::Update<dsl::Find<ownables::table, Uuid>, dsl::Eq<ownables::editor_id, Uuid>>:
+        ExecuteDsl<C>,
+{
+    fn update_stored_model(
+        &mut self,
+        id: Uuid,
+        definition: &TemplateDefinition,
+        editor_id: Uuid,
+    ) -> Result<(), TemplateError> {
+        diesel::update(namespaced_ownables::table.find(id))
+            .set(namespaced_ownables::description.eq(definition.description))
+            .execute(self)
+            .map_err(TemplateError::update(definition.name))?;
+        if let Some(volume) = definition.volume {
+            diesel::update(volumetric_container_models::table.find(id))
+                .set(volumetric_container_models::volume.eq(volume))
+                .execute(self)
+                .map_err(TemplateError::update(definition.name))?;
+        }
+        if let Some(diameter) = definition.diameter {
+            diesel::update(bead_models::table.find(id))
+                .set(bead_models::diameter.eq(diameter))
+                .execute(self)
+                .map_err(TemplateError::update(definition.name))?;
+        }
+        if let Some(mime_type) = definition.mime_type {
+            diesel::update(digital_asset_models::table.find(id))
+                .set(digital_asset_models::mime_type.eq(mime_type))
+                .execute(self)
+                .map_err(TemplateError::update(definition.name))?;
+        }
+        if let Some(reagent) = definition.reagent {
+            diesel::update(reagent_models::table.find(id))
+                .set((
+                    reagent_models::purity.eq(reagent.purity),
+                    reagent_models::cas_code.eq(reagent.cas_code),
+                    reagent_models::molecular_formula.eq(reagent.molecular_formula),
+                ))
+                .execute(self)
+                .map_err(TemplateError::update(definition.name))?;
+        }
+        diesel::update(ownables::table.find(id))
+            .set(ownables::editor_id.eq(editor_id))
+            .execute(self)
+            .map_err(TemplateError::update(definition.name))?;
+        Ok(())
+    }
+}
+
+/// Updates in place the stored reference models whose attributes differ from
+/// their definitions, returning the drifts which were fixed.
+///
+/// The editor of the context is recorded as the last editor of every updated
+/// model. Nothing is ever deleted, and missing models are not created: use
+/// [`seed_reference_catalog`](crate::catalog::seed_reference_catalog) for
+/// that. When no model is missing, the
+/// [`CatalogVersion`](crate::version::CatalogVersion) of the running code is
+/// then recorded in the namespace.
+///
+/// # Arguments
+///
+/// * `context` - The namespace holding the reference models and the user
+///   editing them, or simply the editing user.
+/// * `conn` - A mutable reference to the database connection where the
+///   reference models are stored.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+/// * If any of the updates fails, in which case no model is updated.
+/// * If the metadata table cannot be created or the catalog version cannot
+///   be recorded.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel::RunQueryDsl;
+/// let mut conn = aps_git_conn();
+///
+/// let test_user = user(&mut conn);
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// diesel::sql_query("UPDATE namespaced_ownables SET description = 'Vial' WHERE name = 'Vial 1.5ml'")
+///     .execute(&mut conn)
+///     .expect("Failed to alter the vial description");
+///
+/// let reconciled = reconcile_reference_catalog(&test_user, &mut conn).expect("Failed to reconcile the reference catalog");
+/// assert_eq!(reconciled.len(), 1);
+/// assert_eq!(reconciled[0].template(), VIAL_1_5ML_NAME);
+/// assert!(check_drift(&mut conn).expect("Failed to check the drift").is_empty());
+/// ```
+pub fn reconcile_reference_catalog<'a, C: ReferenceWriter + CatalogMetadata>(
+    context: impl Into<TemplateContext<'a>>,
+    conn: &mut C,
+) -> Result<Vec<TemplateDrift>, TemplateError> {
+    let context = context.into();
+    let editor_id = context.editor.get_column::<users::id>().to_owned();
+
+    conn.transaction(|conn| {
+        let Some(namespace) = conn.find_namespace(context.namespace_name)? else {
+            return Ok(Vec::new());
+        };
+        conn.create_metadata_table()?;
+
+        let mut reconciled = Vec::new();
+        for definition in REFERENCE_TEMPLATES {
+            let Some(stored) =
+                conn.find_stored_model(&namespace, definition.table, definition.name)?
+            else {
+                continue;
+            };
+            let fields = field_drifts(definition, &stored);
+            if fields.is_empty() {
+                continue;
+            }
+            conn.update_stored_model(stored.id, definition, editor_id)?;
+            reconciled.push(TemplateDrift::Changed {
+                template: definition.name,
+                fields,
+            });
+        }
+        record_catalog_version_in(context.namespace_name, conn)?;
+        Ok(reconciled)
+    })
+}
@@ -1,19 +1,30 @@
+use crate::context::{TemplateContext, WithContextId};
+use crate::errors::TemplateError;
+use crate::lookup::{get_or_insert, load_namespace};
+use crate::migrations::original_template_name;
 use aps::aps_namespaces::*;
 use aps::aps_ownables::*;
 use aps::aps_users::*;
-use diesel_builders::BuilderError;
+use diesel::Connection;
 use diesel_builders::prelude::*;
+use rosetta_uuid::Uuid;
 
-/// Returns the reference namespace.
+/// Name of the namespace holding the reference models by default.
+pub const REFERENCE_NAMESPACE_NAME: &str = "aps_reference";
+
+/// Returns the reference namespace, creating it if it does not exist.
 ///
 /// # Arguments
 ///
-/// * `user` - The user creating the namespace.
+/// * `context` - The name of the namespace and the users to create it with, or
+///   simply the creating user.
 /// * `conn` - A mutable reference to the database connection where the
 ///   namespace will be created.
 ///
-/// # Panics
+/// # Errors
 ///
+/// * If the lookup of the namespace fails.
+/// * If the namespace name is rejected by the schema validation.
 /// * If the namespace creation fails.
 ///
 /// # Example
@@ -28,27 +39,153 @@ use diesel_builders::prelude::*;
 /// let reference_namespace2 = reference_namespace(&test_user, &mut conn).expect("Failed to create the reference namespace");
 /// assert_eq!(reference_namespace1, reference_namespace2);
 /// ```
-pub fn reference_namespace<C>(
-    user: &User,
+pub fn reference_namespace<'a, C>(
+    context: impl Into<TemplateContext<'a>>,
     conn: &mut C,
-) -> Result<NestedModel<namespaces::table>, BuilderError<validation_errors::ValidationError>>
+) -> Result<NestedModel<namespaces::table>, TemplateError>
 where
+    C: Connection,
     TableBuilder<namespaces::table>: Insert<C>,
     (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
 {
-    const REFERENCE_NAMESPACE_NAME: &str = "aps_reference";
+    let context = context.into();
 
-    if let Ok(existing) =
-        <(namespaces::name,)>::load_nested_first((REFERENCE_NAMESPACE_NAME,), conn)
-    {
-        return Ok(existing);
-    }
+    get_or_insert(
+        |conn| load_namespace(context.namespace_name, conn),
+        |conn| {
+            namespaces::table::builder()
+                .try_name(context.namespace_name)
+                .map_err(TemplateError::attribute(context.namespace_name, "name"))?
+                .owner_id(context.owner.get_column::<users::id>())
+                .creator_id(context.creator.get_column::<users::id>())
+                .editor_id(context.editor.get_column::<users::id>())
+                .with_id(context.namespace_id())
+                .insert_nested(conn)
+                .map_err(TemplateError::insert(context.namespace_name))
+        },
+        conn,
+    )
+}
+
+/// Base IRI under which the reference namespaces are published.
+pub const REFERENCE_IRI_BASE: &str = "https://w3id.org/aps/";
+
+/// Returns the stable IRI of the template with the provided name in the
+/// provided namespace.
+///
+/// The last segment of the IRI is the name under which the template was first
+/// released, following back the renames of the
+/// [`TEMPLATE_MIGRATIONS`](crate::migrations::TEMPLATE_MIGRATIONS), in
+/// lowercase and with every run of non-alphanumeric characters replaced by an
+/// underscore, so that the IRI survives renames.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the template.
+/// * `template_name` - The name of the template.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// assert_eq!(
+///     template_iri(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name),
+///     "https://w3id.org/aps/aps_reference/vial_1_5ml"
+/// );
+/// assert_eq!(
+///     template_iri(REFERENCE_NAMESPACE_NAME, METHANOL_HPLC.name),
+///     "https://w3id.org/aps/aps_reference/methanol_99_8_hplc_grade"
+/// );
+/// assert_eq!(
+///     template_iri(REFERENCE_NAMESPACE_NAME, SPLIT_CAP_VIAL_1_5ML.name),
+///     "https://w3id.org/aps/aps_reference/splitted_cap_for_vial_1_5ml"
+/// );
+/// ```
+#[must_use]
+pub fn template_iri(namespace_name: &str, template_name: &str) -> String {
+    format!(
+        "{REFERENCE_IRI_BASE}{namespace_name}/{}",
+        template_slug(original_template_name(template_name))
+    )
+}
 
-    namespaces::table::builder()
-        .try_name(REFERENCE_NAMESPACE_NAME)
-        .expect("Failed to set namespace name")
-        .owner_id(user.get_column::<users::id>())
-        .creator_id(user.get_column::<users::id>())
-        .editor_id(user.get_column::<users::id>())
-        .insert_nested(conn)
+/// Returns the UUIDv5 identifier of the namespace with the provided name,
+/// derived from its IRI in the URL namespace.
+fn namespace_v5(namespace_name: &str) -> uuid::Uuid {
+    let iri = format!("{REFERENCE_IRI_BASE}{namespace_name}");
+    uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_URL, iri.as_bytes())
+}
+
+/// Returns the UUIDv5 identifier of the namespace with the provided name,
+/// derived from its IRI in the URL namespace.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace.
+#[must_use]
+pub fn namespace_uuid(namespace_name: &str) -> Uuid {
+    Uuid::from(namespace_v5(namespace_name))
+}
+
+/// Returns the UUIDv5 identifier of the template with the provided name in
+/// the provided namespace, derived from the exact name the template was
+/// introduced with in the namespace of the [`namespace_uuid`].
+///
+/// The identifier only depends on the two names, so that every deployment
+/// creating the template with deterministic identifiers stores it under the
+/// same identifier, and it survives renames. Unlike the [`template_iri`],
+/// whose slug drops punctuation and non-ASCII characters, distinct names
+/// always get distinct identifiers.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the template.
+/// * `template_name` - The name of the template.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_templates::prelude::*;
+///
+/// let vial = template_uuid(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name);
+/// assert_eq!(vial, template_uuid(REFERENCE_NAMESPACE_NAME, VIAL_1_5ML.name));
+/// assert_ne!(vial, template_uuid("lab_reference", VIAL_1_5ML.name));
+/// assert_ne!(vial, namespace_uuid(REFERENCE_NAMESPACE_NAME));
+/// assert_eq!(
+///     template_uuid(REFERENCE_NAMESPACE_NAME, SPLIT_CAP_VIAL_1_5ML.name),
+///     template_uuid(REFERENCE_NAMESPACE_NAME, "Splitted Cap for Vial 1.5ml")
+/// );
+/// assert_ne!(
+///     template_uuid(REFERENCE_NAMESPACE_NAME, "Tube 2ml"),
+///     template_uuid(REFERENCE_NAMESPACE_NAME, "Tube-2ml")
+/// );
+/// assert_ne!(
+///     template_uuid(REFERENCE_NAMESPACE_NAME, "Vial Insert 200μl"),
+///     template_uuid(REFERENCE_NAMESPACE_NAME, "Vial Insert 200 l")
+/// );
+/// ```
+#[must_use]
+pub fn template_uuid(namespace_name: &str, template_name: &str) -> Uuid {
+    Uuid::from(uuid::Uuid::new_v5(
+        &namespace_v5(namespace_name),
+        original_template_name(template_name).as_bytes(),
+    ))
+}
+
+/// Returns the name of the template in lowercase, with every run of
+/// non-alphanumeric characters replaced by an underscore.
+pub(crate) fn template_slug(template_name: &str) -> String {
+    let mut slug = String::with_capacity(template_name.len());
+    for character in template_name.chars() {
+        if character.is_ascii_alphanumeric() {
+            slug.push(character.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    if slug.ends_with('_') {
+        slug.pop();
+    }
+    slug
 }
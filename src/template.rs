@@ -0,0 +1,250 @@
+//! Submodule defining the trait shared by every reference template.
+
+use crate::context::TemplateContext;
+use crate::errors::TemplateError;
+use crate::lookup::find_reference_model;
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+use aps::aps_namespaced_ownables::*;
+use aps::aps_namespaces::*;
+use aps::aps_sample_models::*;
+use aps::aps_sample_source_models::*;
+use diesel::Connection;
+use diesel_builders::{TableBuilder, prelude::*};
+
+/// A reference asset model, identified by its name within the reference
+/// namespace.
+///
+/// Every template of this crate is exposed as a zero-sized type implementing
+/// this trait, next to its get-or-create function, so that code can be written
+/// generically over any reference template. Downstream crates can implement it
+/// for their own templates.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// fn label<T: AssetModelTemplate>() -> String {
+///     format!("{}: {}", T::NAME, T::DESCRIPTION)
+/// }
+/// assert_eq!(label::<Vial1_5ml>(), format!("{}: {}", VIAL_1_5ML.name, VIAL_1_5ML.description));
+///
+/// let test_user = user(&mut conn);
+/// assert!(Vial1_5ml::find(&mut conn).expect("Failed to look up vial model").is_none());
+/// let vial = Vial1_5ml::get_or_create(&test_user, &mut conn).expect("Failed to create vial model");
+/// assert_eq!(vial, vial_1_5ml(&test_user, &mut conn).expect("Failed to create vial model"));
+/// assert_eq!(Vial1_5ml::find(&mut conn).expect("Failed to look up vial model"), Some(vial));
+///
+/// let lab_context = TemplateContext::new(&test_user).namespace_name("lab_reference");
+/// assert!(Vial1_5ml::find_in("lab_reference", &mut conn).expect("Failed to look up vial model").is_none());
+/// let lab_vial = Vial1_5ml::get_or_create(lab_context, &mut conn).expect("Failed to create vial model");
+/// assert_eq!(Vial1_5ml::find_in("lab_reference", &mut conn).expect("Failed to look up vial model"), Some(lab_vial));
+/// ```
+pub trait AssetModelTemplate {
+    /// Table in which the model is stored.
+    type Table: diesel::Table;
+
+    /// Name of the model, unique within its namespace.
+    const NAME: &'static str;
+
+    /// Description of the model.
+    const DESCRIPTION: &'static str;
+
+    /// Returns the model, creating it if it does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The namespace and users to create the model with, or
+    ///   simply the creating user.
+    /// * `conn` - A mutable reference to the database connection where the
+    ///   model will be created.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup of the namespace or of the model fails.
+    /// * If any of the attributes is rejected by the schema validation.
+    /// * If the creation of the namespace or of the model fails.
+    /// * If the model is deprecated and the [`DeprecationPolicy`] of the
+    ///   context rejects it.
+    ///
+    /// [`DeprecationPolicy`]: crate::deprecation::DeprecationPolicy
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<Self::Table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<Self::Table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>;
+
+    /// Returns the model from the default reference namespace, if it exists.
+    ///
+    /// Unlike [`get_or_create`](Self::get_or_create), this never writes to
+    /// the database and needs neither a user nor an insert-capable connection,
+    /// only the lookup bounds of the namespaces and of the table of the model.
+    /// The per-template `find_*` functions are shorthands for it.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup fails for any reason other than the model not existing.
+    fn find<C>(conn: &mut C) -> Result<Option<NestedModel<Self::Table>>, TemplateError>
+    where
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        Self::find_in(REFERENCE_NAMESPACE_NAME, conn)
+    }
+
+    /// Returns the model from the provided namespace, if it exists, without
+    /// writing to the database, see [`find`](Self::find).
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace_name` - The name of the namespace holding the reference
+    ///   models.
+    /// * `conn` - A mutable reference to the database connection.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup fails for any reason other than the model not existing.
+    fn find_in<C>(
+        namespace_name: &str,
+        conn: &mut C,
+    ) -> Result<Option<NestedModel<Self::Table>>, TemplateError>
+    where
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<Self::Table, C>,
+    {
+        find_reference_model(namespace_name, Self::NAME, conn)
+    }
+}
+
+/// A reference sample model, identified by its name within the reference
+/// namespace.
+///
+/// Creating a sample model also creates its sample source model, so the sample
+/// models implement this trait rather than [`AssetModelTemplate`], whose
+/// models only need their own table.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// let mut conn = aps_git_conn();
+///
+/// fn source_name<T: SampleModelTemplate>() -> &'static str {
+///     <T::Source as AssetModelTemplate>::NAME
+/// }
+/// assert_eq!(source_name::<SoilSampleModel>(), SOIL_NAME);
+///
+/// let test_user = user(&mut conn);
+/// assert!(SoilSampleModel::find(&mut conn).expect("Failed to look up the soil sample model").is_none());
+/// let sample = SoilSampleModel::get_or_create(&test_user, &mut conn).expect("Failed to create the soil sample model");
+/// assert_eq!(SoilSampleModel::find(&mut conn).expect("Failed to look up the soil sample model"), Some(sample));
+/// assert!(SoilModel::find(&mut conn).expect("Failed to look up the soil model").is_some());
+/// ```
+pub trait SampleModelTemplate {
+    /// Template of the sample source model of the sample model.
+    type Source: AssetModelTemplate<Table = sample_source_models::table>;
+
+    /// Name of the model, unique within its namespace.
+    const NAME: &'static str;
+
+    /// Description of the model.
+    const DESCRIPTION: &'static str;
+
+    /// Returns the model, creating it and its sample source model if they do
+    /// not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The namespace and users to create the models with, or
+    ///   simply the creating user.
+    /// * `conn` - A mutable reference to the database connection where the
+    ///   models will be created.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup of the namespace or of the models fails.
+    /// * If any of the attributes is rejected by the schema validation.
+    /// * If the creation of the namespace or of the models fails.
+    /// * If the model is deprecated and the [`DeprecationPolicy`] of the
+    ///   context rejects it.
+    ///
+    /// [`DeprecationPolicy`]: crate::deprecation::DeprecationPolicy
+    fn get_or_create<'a, C>(
+        context: impl Into<TemplateContext<'a>>,
+        conn: &mut C,
+    ) -> Result<NestedModel<sample_models::table>, TemplateError>
+    where
+        C: Connection,
+        TableBuilder<sample_models::table>: Insert<C>,
+        TableBuilder<sample_source_models::table>: Insert<C>,
+        TableBuilder<namespaces::table>: Insert<C>,
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_models::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_source_models::table, C>;
+
+    /// Returns the model from the default reference namespace, if it exists,
+    /// see [`AssetModelTemplate::find`].
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup fails for any reason other than the model not existing.
+    fn find<C>(conn: &mut C) -> Result<Option<NestedModel<sample_models::table>>, TemplateError>
+    where
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_models::table, C>,
+    {
+        Self::find_in(REFERENCE_NAMESPACE_NAME, conn)
+    }
+
+    /// Returns the model from the provided namespace, if it exists, see
+    /// [`AssetModelTemplate::find_in`].
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace_name` - The name of the namespace holding the reference
+    ///   models.
+    /// * `conn` - A mutable reference to the database connection.
+    ///
+    /// # Errors
+    ///
+    /// * If the lookup fails for any reason other than the model not existing.
+    fn find_in<C>(
+        namespace_name: &str,
+        conn: &mut C,
+    ) -> Result<Option<NestedModel<sample_models::table>>, TemplateError>
+    where
+        (namespaces::name,): LoadNestedFirst<namespaces::table, C>,
+        (
+            namespaced_ownables::namespace_id,
+            (namespaced_ownables::name,),
+        ): LoadNestedFirst<sample_models::table, C>,
+    {
+        find_reference_model(namespace_name, Self::NAME, conn)
+    }
+}
@@ -0,0 +1,254 @@
+//! Submodule identifying the version of the reference catalog, and recording
+//! in the database which version a namespace was last brought up to date
+//! with.
+//!
+//! A [`CatalogVersion`] is made of the version of this crate and of a FNV-1a
+//! hash of the reference templates. Only the hash decides whether a stored
+//! catalog is current, so that releases which leave the templates untouched
+//! do not require a reconcile.
+
+use std::fmt::{self, Display};
+
+use crate::asset_models::REFERENCE_TEMPLATES;
+use crate::drift::check_drift_in;
+use crate::errors::TemplateError;
+use crate::lookup::ReferenceReader;
+use crate::metadata::CatalogMetadata;
+use crate::reference_namespaces::REFERENCE_NAMESPACE_NAME;
+
+/// Metadata key under which the catalog version is stored.
+pub const CATALOG_VERSION_KEY: &str = "catalog_version";
+
+/// Offset basis of the 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Prime of the 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Incremental 64-bit FNV-1a hash, stable across platforms and releases of
+/// the standard library, unlike [`std::hash::DefaultHasher`].
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    /// Hashes the provided bytes, followed by a separator so that adjacent
+    /// fields cannot be confused.
+    fn field(&mut self, bytes: &[u8]) {
+        for byte in bytes.iter().chain([&0xff]) {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Hashes an optional number, with a missing one hashed as an empty field.
+    fn number(&mut self, value: Option<f32>) {
+        match value {
+            Some(value) => self.field(&value.to_bits().to_le_bytes()),
+            None => self.field(&[]),
+        }
+    }
+}
+
+/// Returns the FNV-1a hash of the attributes of the reference templates
+/// compared by [`check_drift`](crate::drift::check_drift).
+///
+/// The hash covers exactly the compared fields, so that a namespace is only
+/// reported as [`CatalogStatus::Outdated`] for changes which a reconcile can
+/// bring to the database. The parents, sample sources and deprecations are
+/// left out: the former are never updated in place, and the latter are
+/// recorded by every seeding.
+#[must_use]
+pub fn catalog_hash() -> u64 {
+    let mut hash = Fnv1a(FNV_OFFSET_BASIS);
+    for definition in REFERENCE_TEMPLATES {
+        hash.field(definition.table.table_name().as_bytes());
+        hash.field(definition.name.as_bytes());
+        hash.field(definition.description.as_bytes());
+        hash.number(definition.volume);
+        hash.number(definition.diameter);
+        hash.field(definition.mime_type.unwrap_or_default().as_bytes());
+        hash.number(definition.reagent.map(|reagent| reagent.purity));
+        for property in definition.reagent.map_or(["", ""], |reagent| {
+            [reagent.cas_code, reagent.molecular_formula]
+        }) {
+            hash.field(property.as_bytes());
+        }
+    }
+    hash.0
+}
+
+/// Version of the reference catalog, displayed and stored as
+/// `<crate version>+fnv1a.<hash>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CatalogVersion {
+    /// Version of the crate which defined the catalog.
+    pub crate_version: String,
+    /// Hash of the reference templates, see [`catalog_hash`].
+    pub content_hash: u64,
+}
+
+impl CatalogVersion {
+    /// Returns the version of the catalog defined by the running code.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use aps_templates::prelude::*;
+    ///
+    /// let version = CatalogVersion::current();
+    /// assert_eq!(version.content_hash, catalog_hash());
+    /// assert_eq!(CatalogVersion::parse(&version.to_string()), Some(version));
+    /// assert_eq!(CatalogVersion::parse("0.1.0"), None);
+    /// ```
+    #[must_use]
+    pub fn current() -> Self {
+        Self {
+            crate_version: env!("CARGO_PKG_VERSION").to_owned(),
+            content_hash: catalog_hash(),
+        }
+    }
+
+    /// Parses a version formatted as `<crate version>+fnv1a.<hash>`, returning
+    /// `None` if the provided marker is malformed.
+    #[must_use]
+    pub fn parse(marker: &str) -> Option<Self> {
+        let (crate_version, hash) = marker.rsplit_once("+fnv1a.")?;
+        Some(Self {
+            crate_version: crate_version.to_owned(),
+            content_hash: u64::from_str_radix(hash, 16).ok()?,
+        })
+    }
+}
+
+impl Display for CatalogVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+fnv1a.{:016x}", self.crate_version, self.content_hash)
+    }
+}
+
+/// Comparison of the catalog version stored in a namespace with the one of
+/// the running code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CatalogStatus {
+    /// No catalog version was ever recorded in the namespace.
+    Unrecorded,
+    /// The stored catalog has the same templates as the running code.
+    Current,
+    /// The stored catalog was recorded with different templates, or its
+    /// marker cannot be parsed.
+    Outdated {
+        /// The stored marker.
+        stored: String,
+    },
+}
+
+impl CatalogStatus {
+    /// Returns whether the namespace should be seeded and reconciled to match
+    /// the running code.
+    #[must_use]
+    pub fn needs_reconcile(&self) -> bool {
+        *self != Self::Current
+    }
+}
+
+/// Returns the catalog version stored in the provided namespace, if any.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read.
+pub fn stored_catalog_version_in<C: CatalogMetadata>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<Option<String>, TemplateError> {
+    conn.read_metadata(namespace_name, CATALOG_VERSION_KEY)
+}
+
+/// Compares the catalog version stored in the default reference namespace
+/// with the one of the running code.
+///
+/// # Arguments
+///
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read.
+///
+/// # Example
+///
+/// ```rust
+/// use aps_test_utils::{aps_git_conn, user};
+/// use aps_templates::prelude::*;
+/// use diesel::RunQueryDsl;
+/// let mut conn = aps_git_conn();
+///
+/// assert_eq!(catalog_status(&mut conn).expect("Failed to read the catalog version"), CatalogStatus::Unrecorded);
+/// let test_user = user(&mut conn);
+/// seed_reference_catalog(&test_user, &mut conn).expect("Failed to seed the reference catalog");
+/// assert_eq!(catalog_status(&mut conn).expect("Failed to read the catalog version"), CatalogStatus::Current);
+///
+/// diesel::sql_query("UPDATE aps_templates_metadata SET value = '0.0.1+fnv1a.0000000000000000'")
+///     .execute(&mut conn)
+///     .expect("Failed to alter the catalog version");
+/// let status = catalog_status(&mut conn).expect("Failed to read the catalog version");
+/// assert!(status.needs_reconcile());
+///
+/// reconcile_reference_catalog(&test_user, &mut conn).expect("Failed to reconcile the reference catalog");
+/// assert_eq!(catalog_status(&mut conn).expect("Failed to read the catalog version"), CatalogStatus::Current);
+/// ```
+pub fn catalog_status<C: CatalogMetadata>(conn: &mut C) -> Result<CatalogStatus, TemplateError> {
+    catalog_status_in(REFERENCE_NAMESPACE_NAME, conn)
+}
+
+/// Compares the catalog version stored in the provided namespace with the
+/// one of the running code.
+///
+/// # Arguments
+///
+/// * `namespace_name` - The name of the namespace holding the reference
+///   models.
+/// * `conn` - A mutable reference to the database connection.
+///
+/// # Errors
+///
+/// * If the metadata cannot be read.
+pub fn catalog_status_in<C: CatalogMetadata>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<CatalogStatus, TemplateError> {
+    Ok(match stored_catalog_version_in(namespace_name, conn)? {
+        None => CatalogStatus::Unrecorded,
+        Some(stored) => match CatalogVersion::parse(&stored) {
+            Some(version) if version.content_hash == catalog_hash() => CatalogStatus::Current,
+            _ => CatalogStatus::Outdated { stored },
+        },
+    })
+}
+
+/// Records the version of the running code in the provided namespace, unless
+/// some reference model is missing or drifted, in which case the stored
+/// version is left untouched so that it keeps telling that a reconcile is
+/// needed.
+///
+/// # Errors
+///
+/// * If any of the lookups fails.
+/// * If the metadata cannot be written.
+pub(crate) fn record_catalog_version_in<C: ReferenceReader + CatalogMetadata>(
+    namespace_name: &str,
+    conn: &mut C,
+) -> Result<(), TemplateError> {
+    if check_drift_in(namespace_name, conn)?.is_empty() {
+        conn.write_metadata(
+            namespace_name,
+            CATALOG_VERSION_KEY,
+            &CatalogVersion::current().to_string(),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! Concurrency test hammering the templates from several threads.
+//!
+//! The test needs a local Postgres database with the APS schema already
+//! migrated, whose URL is read from the `APS_TEMPLATES_DATABASE_URL`
+//! environment variable. It is ignored by default, run it with:
+//!
+//! ```sh
+//! APS_TEMPLATES_DATABASE_URL=postgres://localhost/aps cargo test --test concurrent_seeding -- --ignored
+//! ```
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use aps_templates::prelude::*;
+use aps_test_utils::user;
+use diesel::{Connection, PgConnection};
+
+/// Number of workers racing on the same templates.
+const WORKERS: usize = 8;
+
+/// Function getting or creating the model of a template.
+type Template = fn(TemplateContext<'_>, &mut PgConnection) -> Result<(), TemplateError>;
+
+/// Returns the names of the provided templates along with the functions
+/// getting or creating their models.
+macro_rules! templates {
+    ($($definition:ident => $function:ident,)*) => {
+        [$((
+            $definition.name,
+            (|context: TemplateContext<'_>, conn: &mut PgConnection| {
+                $function(context, conn).map(drop)
+            }) as Template,
+        ),)*]
+    };
+}
+
+/// Returns a new connection to the database of the test.
+fn connection() -> PgConnection {
+    let url = std::env::var("APS_TEMPLATES_DATABASE_URL")
+        .expect("APS_TEMPLATES_DATABASE_URL must point to a migrated APS database");
+    PgConnection::establish(&url).expect("Failed to connect to the test database")
+}
+
+/// Runs `work` on `WORKERS` threads released at the same moment, each with
+/// its own connection and user, and returns their results.
+fn race<T, F>(work: F) -> Vec<T>
+where
+    T: Send + 'static,
+    F: Fn(&mut PgConnection) -> T + Send + Sync + 'static,
+{
+    let barrier = Arc::new(Barrier::new(WORKERS));
+    let work = Arc::new(work);
+    let handles: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let barrier = Arc::clone(&barrier);
+            let work = Arc::clone(&work);
+            thread::spawn(move || {
+                let mut conn = connection();
+                barrier.wait();
+                work(&mut conn)
+            })
+        })
+        .collect();
+    handles
+        .into_iter()
+        .map(|handle| handle.join().expect("A worker panicked"))
+        .collect()
+}
+
+#[test]
+#[ignore = "requires APS_TEMPLATES_DATABASE_URL"]
+fn concurrent_template_calls_return_the_same_model() {
+    // The standard rack has no function of its own, and is created as the
+    // parent of the rack for conical centrifugal tubes of 50ml.
+    let templates = templates! {
+        BEAD_3MM => bead_3mm,
+        BOTTLE_1L => bottle_1l,
+        POLYSTYRENE_BOX => polystyrene_box,
+        VIAL_RACK_1_5ML => vial_rack_1_5ml,
+        CONICAL_CENTRIFUGAL_TUBE_50ML => conical_centrifugal_tube_50ml,
+        STANDARD_RACK => conical_centrifugal_tube_50ml_rack,
+        CONICAL_CENTRIFUGAL_TUBE_50ML_RACK => conical_centrifugal_tube_50ml_rack,
+        SAFELOCK_TUBES_2ML => safelock_tubes_2ml,
+        SEALED_CAP_VIAL_1_5ML => sealed_cap_vial_1_5ml,
+        SPLIT_CAP_VIAL_1_5ML => split_cap_vial_1_5ml,
+        VIAL_INSERT_200UL => vial_insert_200ul,
+        VIAL_1_5ML => vial_1_5ml,
+        COFFEE_FILTER_WRAPPER => coffee_filter_wrapper,
+        BALL_MILL_MACHINE => ball_mill_machine,
+        SAFELOCK_CENTRIFUGE => safelock_centrifuge,
+        FREEZE_DRYER => freeze_dryer,
+        FREEZER => freezer,
+        PHONE_MODEL => phone_model,
+        PIPETTE_TIP_200UL => pipette_tip_200ul,
+        PIPETTE_TIP_1000UL => pipette_tip_1000ul,
+        PIPETTE_200UL => pipette_200ul,
+        PIPETTE_1000UL => pipette_1000ul,
+        VOLUME_MEASURING_DEVICE_MODEL => volume_measuring_device_model,
+        WEIGHING_SCALE => weighing_scale,
+        MARKER_ARROW_MODEL => marker_arrow_model,
+        PHOTOGRAPH_MODEL => photograph_model,
+        PANEL_MODEL => panel_model,
+        GLOVE_MODEL => glove_model,
+        DISTILLED_WATER => distilled_water,
+        ABSOLUTE_ETHANOL => absolute_ethanol,
+        FORMIC_ACID => formic_acid,
+        LIQUID_NITROGEN => liquid_nitrogen,
+        METHANOL_HPLC => methanol_hplc,
+        SCALPEL_MODEL => scalpel_model,
+        SCISSOR_MODEL => scissor_model,
+        ORGANISM_MODEL => organism_model,
+        ORGANISM_SAMPLE_MODEL => organism_sample_model,
+        SOIL_MODEL => soil_model,
+        SOIL_SAMPLE_MODEL => soil_sample_model,
+    };
+    // Every template races in a namespace of its own, so that its model and
+    // those it depends on are always created by the workers of the race.
+    let run = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("The clock is set before the Unix epoch")
+        .as_nanos();
+    for (position, definition) in REFERENCE_TEMPLATES.iter().enumerate() {
+        let template = templates
+            .iter()
+            .find(|(name, _)| *name == definition.name)
+            .map(|(_, template)| *template)
+            .unwrap_or_else(|| panic!("`{}` has no function to race", definition.name));
+        let namespace_name = format!("concurrent_seeding_{run}_{position}");
+        let models = race(move |conn| {
+            let test_user = user(conn);
+            let context = TemplateContext::new(&test_user).namespace_name(&namespace_name);
+            template(context, conn).expect("Failed to get or create the model");
+            let namespace = conn
+                .find_namespace(&namespace_name)
+                .expect("Failed to look up the namespace")
+                .expect("The namespace was not created");
+            conn.find_stored_model(&namespace, definition.table, definition.name)
+                .expect("Failed to look up the model")
+                .expect("The model was not created")
+        });
+        assert!(
+            models.windows(2).all(|pair| pair[0] == pair[1]),
+            "the workers returned different models of `{}`",
+            definition.name
+        );
+    }
+}
+
+#[test]
+#[ignore = "requires APS_TEMPLATES_DATABASE_URL"]
+fn concurrent_seeding_returns_the_same_catalog() {
+    let catalogs = race(|conn| {
+        let test_user = user(conn);
+        seed_reference_catalog(&test_user, conn).expect("Failed to seed the reference catalog")
+    });
+    assert!(catalogs.windows(2).all(|pair| pair[0] == pair[1]));
+}
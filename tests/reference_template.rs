@@ -0,0 +1,39 @@
+//! Test declaring a template with the exported `reference_template!` macro
+//! from outside the crate, so that its expansion only relies on the public
+//! items of `aps_templates`.
+//!
+//! Besides the test below, the macro generates `vial_10ml_is_idempotent` from
+//! the `test` block, which runs here because integration tests are compiled
+//! with `cfg(test)`.
+
+use aps_templates::prelude::*;
+use aps_test_utils::{aps_git_conn, user};
+
+aps_templates::reference_template! {
+    /// Returns the vial 10ml model, creating it if it does not exist.
+    pub fn vial_10ml {
+        table: volumetric_container_models,
+        name: "Vial 10ml",
+        description: "Vial of 10 ml used for extracts storage",
+        volume: 0.01,
+    }
+    test {
+        conn: aps_git_conn(),
+        user: user,
+    }
+}
+
+#[test]
+fn downstream_template_matches_its_definition() {
+    let mut conn = aps_git_conn();
+    let test_user = user(&mut conn);
+
+    assert_eq!(Vial10ml::NAME, VIAL_10ML.name);
+    assert_eq!(Vial10ml::DESCRIPTION, VIAL_10ML.description);
+    assert_eq!(VIAL_10ML.volume, Some(0.01));
+    let vial = Vial10ml::get_or_create(&test_user, &mut conn).expect("Failed to create vial model");
+    assert_eq!(
+        find_vial_10ml(&mut conn).expect("Failed to look up the vial 10ml model"),
+        Some(vial)
+    );
+}
@@ -0,0 +1,184 @@
+//! Test comparing the rows inserted by the application with the rows inserted
+//! by the SQL script of [`export_seed_sql`].
+//!
+//! The catalog is seeded twice in the same database, through the application
+//! in one namespace and through the script in another, with the same users
+//! and deterministic identifiers. The rows of every table are then compared,
+//! with the identifiers of the models replaced by their names.
+
+use std::iter;
+
+use aps_templates::prelude::*;
+use aps_test_utils::{aps_git_conn, user};
+use diesel::connection::SimpleConnection;
+use diesel::sql_types::Text;
+use diesel::{PgConnection, QueryableByName, RunQueryDsl};
+
+/// Namespace seeded through the application.
+const API_NAMESPACE: &str = "api_reference";
+/// Namespace seeded through the SQL script.
+const SCRIPT_NAMESPACE: &str = "script_reference";
+
+/// Row of a table, rendered as text.
+#[derive(QueryableByName)]
+struct Row {
+    #[diesel(sql_type = Text)]
+    row: String,
+}
+
+/// Name and identifier of a namespace or of a model.
+#[derive(QueryableByName)]
+struct Model {
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    id: String,
+}
+
+/// Returns the rows selected by `query` for the provided namespace, bound to
+/// `$1`, in order.
+fn rows(query: &str, namespace_name: &str, conn: &mut PgConnection) -> Vec<String> {
+    diesel::sql_query(format!("{query} ORDER BY row"))
+        .bind::<Text, _>(namespace_name)
+        .load::<Row>(conn)
+        .expect("Failed to load the rows")
+        .into_iter()
+        .map(|row| row.row)
+        .collect()
+}
+
+/// Returns the query selecting the rows of the provided model table, with
+/// the name of the model and the names of its parent and sample source
+/// models in place of their identifiers.
+fn model_rows(table: &str) -> String {
+    format!(
+        "SELECT concat_ws(' ', o.name, \
+             to_jsonb(t) - 'id' - 'namespace_id' - 'parent_model_id' - 'sample_source_model_id', \
+             to_jsonb(t) ->> 'namespace_id' = n.id::text, p.name, s.name) AS row \
+         FROM {table} t \
+         JOIN namespaced_ownables o ON o.id = t.id \
+         JOIN namespaces n ON n.id = o.namespace_id \
+         LEFT JOIN namespaced_ownables p ON p.id = (to_jsonb(t) ->> 'parent_model_id')::uuid \
+         LEFT JOIN namespaced_ownables s ON s.id = (to_jsonb(t) ->> 'sample_source_model_id')::uuid \
+         WHERE n.name = $1"
+    )
+}
+
+/// Asserts that the provided namespace and its models have the identifiers
+/// of their names and templates.
+fn assert_deterministic_ids(namespace_name: &str, conn: &mut PgConnection) {
+    let namespaces =
+        diesel::sql_query("SELECT name, id::text AS id FROM namespaces WHERE name = $1")
+            .bind::<Text, _>(namespace_name)
+            .load::<Model>(conn)
+            .expect("Failed to load the namespace");
+    assert_eq!(namespaces.len(), 1);
+    assert_eq!(namespaces[0].id, namespace_uuid(namespace_name).to_string());
+
+    let models = diesel::sql_query(
+        "SELECT o.name, o.id::text AS id FROM namespaced_ownables o \
+         JOIN namespaces n ON n.id = o.namespace_id WHERE n.name = $1",
+    )
+    .bind::<Text, _>(namespace_name)
+    .load::<Model>(conn)
+    .expect("Failed to load the models");
+    assert_eq!(models.len(), REFERENCE_TEMPLATES.len());
+    for model in models {
+        assert_eq!(
+            model.id,
+            template_uuid(namespace_name, &model.name).to_string(),
+            "`{}` in `{namespace_name}` does not have the identifier of its template",
+            model.name
+        );
+    }
+}
+
+#[test]
+fn seed_script_inserts_the_rows_of_the_application() {
+    let mut conn = aps_git_conn();
+    let owner = user(&mut conn);
+    let creator = user(&mut conn);
+    let editor = user(&mut conn);
+    let context = TemplateContext::new(&owner)
+        .creator(&creator)
+        .editor(&editor)
+        .deterministic_ids(true);
+
+    let api = context.namespace_name(API_NAMESPACE);
+    seed_reference_catalog(api, &mut conn).expect("Failed to seed the reference catalog");
+    init_compatibility_rules(api, &mut conn).expect("Failed to initialize the compatibility rules");
+    conn.batch_execute(&export_seed_sql(context.namespace_name(SCRIPT_NAMESPACE)))
+        .expect("Failed to apply the seed script");
+
+    for namespace_name in [API_NAMESPACE, SCRIPT_NAMESPACE] {
+        assert_deterministic_ids(namespace_name, &mut conn);
+    }
+
+    let mut tables: Vec<_> = REFERENCE_TEMPLATES
+        .iter()
+        .flat_map(|definition| {
+            iter::successors(Some(definition.table), |table| table.extended_table())
+        })
+        .map(ModelTable::table_name)
+        .chain(iter::once("asset_models"))
+        .collect();
+    tables.sort_unstable();
+    tables.dedup();
+    assert!(tables.contains(&"reagent_models"));
+
+    let mut queries = vec![
+        (
+            "namespaces",
+            "SELECT concat_ws(' ', w.owner_id, w.creator_id, w.editor_id) AS row \
+             FROM namespaces n JOIN ownables w ON w.id = n.id WHERE n.name = $1"
+                .to_owned(),
+        ),
+        (
+            "ownables",
+            "SELECT concat_ws(' ', o.name, w.owner_id, w.creator_id, w.editor_id) AS row \
+             FROM namespaced_ownables o \
+             JOIN namespaces n ON n.id = o.namespace_id \
+             JOIN ownables w ON w.id = o.id WHERE n.name = $1"
+                .to_owned(),
+        ),
+        (
+            "namespaced_ownables",
+            "SELECT concat_ws(' ', o.name, o.description) AS row \
+             FROM namespaced_ownables o \
+             JOIN namespaces n ON n.id = o.namespace_id WHERE n.name = $1"
+                .to_owned(),
+        ),
+        (
+            "asset_compatibility_rules",
+            "SELECT concat_ws(' ', l.name, r.name, c.creator_id) AS row \
+             FROM asset_compatibility_rules c \
+             JOIN namespaced_ownables l ON l.id = c.left_asset_model_id \
+             JOIN namespaced_ownables r ON r.id = c.right_asset_model_id \
+             JOIN namespaces n ON n.id = l.namespace_id WHERE n.name = $1"
+                .to_owned(),
+        ),
+        (
+            "container_compatibility_rules",
+            "SELECT concat_ws(' ', l.name, r.name, c.quantity, c.creator_id) AS row \
+             FROM container_compatibility_rules c \
+             JOIN namespaced_ownables l ON l.id = c.container_model_id \
+             JOIN namespaced_ownables r ON r.id = c.contained_asset_model_id \
+             JOIN namespaces n ON n.id = l.namespace_id WHERE n.name = $1"
+                .to_owned(),
+        ),
+        (
+            "aps_templates_metadata",
+            "SELECT concat_ws(' ', key, value) AS row \
+             FROM aps_templates_metadata WHERE namespace_name = $1"
+                .to_owned(),
+        ),
+    ];
+    queries.extend(tables.into_iter().map(|table| (table, model_rows(table))));
+
+    for (table, query) in queries {
+        let api_rows = rows(&query, API_NAMESPACE, &mut conn);
+        let script_rows = rows(&query, SCRIPT_NAMESPACE, &mut conn);
+        assert!(!api_rows.is_empty(), "no rows of `{table}` were inserted");
+        assert_eq!(api_rows, script_rows, "the rows of `{table}` differ");
+    }
+}